@@ -0,0 +1,76 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn bench_lengths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_prelowered");
+    // 16 bytes is the last length on the short block loader, 17 the first on
+    // the general one
+    for len in [4usize, 8, 16, 17, 64, 1024] {
+        let input = vec![b'a'; len];
+        group.bench_function(format!("{} bytes", len), |b| {
+            b.iter(|| pvpgn_hash_rs::hash_prelowered(black_box(&input)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_string(c: &mut Criterion) {
+    c.bench_function("get_hash_string", |b| {
+        b.iter(|| pvpgn_hash_rs::get_hash_string(black_box("12345")))
+    });
+}
+
+// The per-byte `format!` and `join` that `get_hash_string` used to do, kept
+// here so a regression back to it shows up in the numbers.
+fn hex_join(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+fn bench_hex(c: &mut Criterion) {
+    let hash = pvpgn_hash_rs::parse_hash_hex("460e0af6c1828a93fe887cbe103d6ca6ab97a0e4").unwrap();
+    assert_eq!(hex_join(hash.as_bytes()), hash.to_string());
+
+    let mut group = c.benchmark_group("hex formatting");
+    group.bench_function("vec string join", |b| {
+        b.iter(|| hex_join(black_box(hash.as_bytes())))
+    });
+    group.bench_function("display", |b| b.iter(|| black_box(&hash).to_string()));
+    group.bench_function("to_hex_bytes", |b| {
+        b.iter(|| black_box(&hash).to_hex_bytes())
+    });
+    group.finish();
+}
+
+#[cfg(feature = "simd")]
+fn bench_batch(c: &mut Criterion) {
+    // already lowercase, so the scalar side can use hash_prelowered rather
+    // than the per-byte iterator of hash_bytes_iter; the SIMD side folds the
+    // 64 bytes it loads with a plain slice loop
+    let passwords: Vec<Vec<u8>> = (0..64)
+        .map(|i| format!("password{}", i).into_bytes())
+        .collect();
+    let refs: Vec<&[u8]> = passwords.iter().map(Vec::as_slice).collect();
+
+    let mut group = c.benchmark_group("batch of 64");
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            refs.iter()
+                .map(|p| pvpgn_hash_rs::hash_prelowered(black_box(p)))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("simd", |b| {
+        b.iter(|| pvpgn_hash_rs::simd::hash_batch_simd(black_box(&refs)))
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "simd"))]
+criterion_group!(benches, bench_lengths, bench_string, bench_hex);
+#[cfg(feature = "simd")]
+criterion_group!(benches, bench_lengths, bench_string, bench_hex, bench_batch);
+criterion_main!(benches);
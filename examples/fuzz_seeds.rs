@@ -0,0 +1,58 @@
+//! Writes the seed corpus for the `hash` fuzz target.
+//!
+//!     cargo run --example fuzz_seeds
+//!     cargo fuzz run hash
+//!
+//! The seeds land in `fuzz/corpus/hash`, where `cargo fuzz run` looks by
+//! default. The output is deterministic, so rerunning only overwrites the
+//! same files. Pass a directory to write somewhere else.
+
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+fn seeds() -> Vec<(String, Vec<u8>)> {
+    let mut seeds: Vec<(String, Vec<u8>)> = [
+        ("empty", Vec::new()),
+        ("len-1", b"a".to_vec()),
+        ("vector-12345", b"12345".to_vec()),
+        ("mixed-case", b"PaSsWoRd".to_vec()),
+        ("all-ascii", (0x20..0x7f).collect()),
+        ("all-bytes", (0..=255).collect()),
+        ("nul-inside", b"abc\0def".to_vec()),
+        ("nul-only", vec![0]),
+        ("nul-trailing", b"abc\0\0\0".to_vec()),
+        ("utf8-two-byte", "Café ÄÖÜ".as_bytes().to_vec()),
+        ("utf8-three-byte", "密码パスワード".as_bytes().to_vec()),
+        ("utf8-four-byte", "🔑🎮".as_bytes().to_vec()),
+        ("utf8-fold-changes-len", "İSTANBUL".as_bytes().to_vec()),
+        ("utf8-truncated", "é".as_bytes()[..1].to_vec()),
+    ]
+    .into_iter()
+    .map(|(name, data)| (name.to_owned(), data))
+    .collect();
+
+    // only the first 64 bytes reach the expansion, and 1024 is the cap
+    for len in [63, 64, 65, 1023, 1024, 1025] {
+        let data = b"Ab1".iter().copied().cycle().take(len).collect();
+        seeds.push((format!("len-{}", len), data));
+    }
+    seeds.push(("full-1024-upper".to_owned(), vec![b'Z'; 1024]));
+    seeds.push(("full-1024-nul".to_owned(), vec![0; 1024]));
+
+    seeds
+}
+
+fn main() -> io::Result<()> {
+    let dir = env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/hash"));
+    fs::create_dir_all(&dir)?;
+
+    let seeds = seeds();
+    for (name, data) in &seeds {
+        fs::write(dir.join(name), data)?;
+    }
+    println!("wrote {} seeds to {}", seeds.len(), dir.display());
+    Ok(())
+}
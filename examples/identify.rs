@@ -0,0 +1,78 @@
+//! Reports which hash algorithm reproduces a stored hash for a known password.
+//!
+//! Useful when migrating accounts from a system whose hash scheme is unknown:
+//!
+//!     cargo run --example identify -- <stored_hex> <password>
+//!
+//! Exits with 0 when at least one algorithm matches and 1 otherwise.
+
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use std::process::ExitCode;
+
+struct Candidate {
+    name: &'static str,
+    hex: Option<String>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn candidates(password: &str) -> Vec<Candidate> {
+    let lower = password.to_lowercase();
+
+    vec![
+        Candidate {
+            name: "pvpgn broken sha1",
+            hex: pvpgn_hash_rs::get_hash_string(password).ok(),
+        },
+        Candidate {
+            name: "sha1",
+            hex: Some(to_hex(&Sha1::digest(password.as_bytes()))),
+        },
+        Candidate {
+            name: "sha1 (lowercased)",
+            hex: Some(to_hex(&Sha1::digest(lower.as_bytes()))),
+        },
+        Candidate {
+            name: "md5",
+            hex: Some(to_hex(&Md5::digest(password.as_bytes()))),
+        },
+        Candidate {
+            name: "md5 (lowercased)",
+            hex: Some(to_hex(&Md5::digest(lower.as_bytes()))),
+        },
+    ]
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() != 2 {
+        eprintln!("usage: identify <stored_hex> <password>");
+        return ExitCode::from(2);
+    }
+
+    let stored = args[0].trim().to_ascii_lowercase();
+    let password = &args[1];
+
+    let mut matched = false;
+    for candidate in candidates(password) {
+        let status = match &candidate.hex {
+            Some(hex) if *hex == stored => {
+                matched = true;
+                "match"
+            }
+            Some(_) => "no match",
+            None => "not applicable",
+        };
+        println!("{:<20} {}", candidate.name, status);
+    }
+
+    if matched {
+        ExitCode::SUCCESS
+    } else {
+        println!("no known algorithm reproduces the stored hash");
+        ExitCode::from(1)
+    }
+}
@@ -0,0 +1,114 @@
+//! A login handler wired the way a web front-end to a PvPGN account store
+//! would use this crate, without pulling in an HTTP framework:
+//!
+//!     cargo run --example web_login
+//!
+//! Each request looks up the stored `passhash1` for the user, checks the
+//! submitted password with `verify_str`, and maps the outcome, including every
+//! kind of `HashError`, to a status code.
+
+use pvpgn_hash_rs::{dummy_verify, get_hash_string, verify_str, HashError, HashErrorKind};
+use std::collections::HashMap;
+
+struct LoginRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn new(status: u16, body: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Account names mapped to stored hex hashes, as read from account files.
+struct AccountStore {
+    accounts: HashMap<String, String>,
+}
+
+impl AccountStore {
+    fn passhash1(&self, username: &str) -> Option<&str> {
+        self.accounts
+            .get(&username.to_lowercase())
+            .map(String::as_str)
+    }
+}
+
+fn login(store: &AccountStore, request: &LoginRequest) -> Response {
+    // Unknown users get the same answer as wrong passwords, after the same
+    // hashing work, so neither the response nor its timing reveals which
+    // accounts exist.
+    let Some(stored) = store.passhash1(request.username) else {
+        let _ = dummy_verify(request.password);
+        return Response::new(401, "invalid username or password");
+    };
+
+    match verify_str(request.password, stored) {
+        Ok(true) => Response::new(200, format!("welcome, {}", request.username)),
+        Ok(false) => Response::new(401, "invalid username or password"),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: HashError) -> Response {
+    match e.kind() {
+        // problems with what the client sent; an unknown user with the same
+        // password gets 401, so anything else here would reveal the account
+        HashErrorKind::Empty
+        | HashErrorKind::TooLong
+        | HashErrorKind::TooShort
+        | HashErrorKind::InvalidUtf8 => Response::new(401, "invalid username or password"),
+        // problems with what the server has stored
+        HashErrorKind::InvalidHexLength
+        | HashErrorKind::InvalidHexDigit
+        | HashErrorKind::InvalidBase32Length
+        | HashErrorKind::InvalidBase32Digit
+        | HashErrorKind::AccountFieldMissing
+        | HashErrorKind::AccountFieldMalformed => {
+            eprintln!("corrupt account record: {}", e);
+            Response::new(500, "internal error")
+        }
+        HashErrorKind::RateLimited => Response::new(429, "too many attempts"),
+        HashErrorKind::Io => {
+            eprintln!("account store unavailable: {}", e);
+            Response::new(503, "try again later")
+        }
+        _ => Response::new(500, "internal error"),
+    }
+}
+
+fn main() {
+    let mut accounts = HashMap::new();
+    accounts.insert(
+        "alice".to_string(),
+        get_hash_string("12345").expect("valid password"),
+    );
+    accounts.insert("mallory".to_string(), "not a hash".to_string());
+    let store = AccountStore { accounts };
+
+    let long = "x".repeat(2000);
+    let requests = [
+        ("Alice", "12345"),
+        ("alice", "wrong"),
+        ("bob", "12345"),
+        ("alice", ""),
+        ("alice", long.as_str()),
+        ("bob", ""),
+        ("bob", long.as_str()),
+        ("mallory", "12345"),
+    ];
+
+    for (username, password) in requests {
+        let response = login(&store, &LoginRequest { username, password });
+        println!("{:<8} -> {} {}", username, response.status, response.body);
+    }
+}
@@ -0,0 +1,33 @@
+//! Feeds arbitrary bytes through the hashing entry points and checks that
+//! they never panic and agree with each other.
+//!
+//! Seed the corpus first with `cargo run --example fuzz_seeds`, then run
+//! `cargo fuzz run hash` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pvpgn_hash_rs::{hash_bytes_iter, hash_with, hash_with_scratch, BrokenSha1, HashOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let expected = hash_bytes_iter(data.iter().copied());
+
+    assert_eq!(hash_with(data, HashOptions::pvpgn()), expected);
+    // hash_bytes_iter stops at 1025 bytes, the others report the full length
+    assert_eq!(hash_with_scratch(data, &mut [0; 256]).is_ok(), expected.is_ok());
+
+    let mut hasher = BrokenSha1::new();
+    let split = data.len() / 2;
+    let streamed = hasher
+        .update(&data[..split])
+        .and_then(|()| hasher.update(&data[split..]))
+        .and_then(|()| hasher.clone().finalize());
+    assert_eq!(streamed.is_ok(), expected.is_ok());
+    if let Ok(hash) = expected {
+        assert_eq!(streamed, Ok(hash));
+    }
+
+    if let Ok(password) = std::str::from_utf8(data) {
+        let _ = pvpgn_hash_rs::get_hash_string(password);
+    }
+});
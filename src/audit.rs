@@ -0,0 +1,223 @@
+//! Helpers exposing the internals of the hash for analysis and teaching.
+//!
+//! Nothing in this module is meant for authentication. It exists to show why
+//! the construction is weak, and its output must never be stored or compared
+//! in place of a real digest.
+
+use crate::{
+    calculate_hash, compress, compress_traced, expand, fold_into, load_block, Hash, HashError, IV,
+    MAX_INPUT_LEN,
+};
+
+/// Returns the five working variables after the 80 rounds, before the
+/// initial values are added back on to produce the digest.
+///
+/// The digest is exactly `raw_state(p)[i].wrapping_add(IV[i])` serialized
+/// big-endian, so this value is a chaining value in all but name. Exposing it
+/// is useful for demonstrating length-extension-like properties of the
+/// construction and is unsafe for any other purpose.
+pub fn raw_state(password: &[u8]) -> Result<[u32; 5], HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let len = fold_into(password, &mut buffer)?;
+
+    let mut w = load_block(&buffer[..len]);
+    expand(&mut w);
+    Ok(compress(IV, &w))
+}
+
+/// Hashes `password` as given, with its ASCII letters lowercased and with
+/// them uppercased, in that order, through the
+/// [`get_hash_string`](crate::get_hash_string) path.
+///
+/// Only `A`-`Z` and `a`-`z` change, so all three digests should be identical;
+/// a difference means the fold is not being applied before hashing.
+pub fn hash_case_variants(password: &str) -> Result<Vec<(String, Hash)>, HashError> {
+    [
+        password.to_string(),
+        password.to_ascii_lowercase(),
+        password.to_ascii_uppercase(),
+    ]
+    .into_iter()
+    .map(|variant| {
+        let hash = calculate_hash(&variant)?;
+        Ok((variant, hash))
+    })
+    .collect()
+}
+
+/// How the working variables of two inputs diverge over the 80 rounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvalancheReport {
+    /// Number of differing bits across `a..e` after each round, out of 160.
+    pub rounds: [u32; 80],
+}
+
+impl AvalancheReport {
+    /// The first round after which the two states differ, if any.
+    pub fn first_divergence(&self) -> Option<usize> {
+        self.rounds.iter().position(|&bits| bits != 0)
+    }
+}
+
+/// Compresses `a` and `b` side by side and counts the bit differences
+/// between their working variables after every round.
+pub fn avalanche(a: &[u8], b: &[u8]) -> Result<AvalancheReport, HashError> {
+    let trace_a = trace(a)?;
+    let trace_b = trace(b)?;
+
+    let mut rounds = [0u32; 80];
+    for (bits, (x, y)) in rounds.iter_mut().zip(trace_a.iter().zip(&trace_b)) {
+        *bits = x.iter().zip(y).map(|(x, y)| (x ^ y).count_ones()).sum();
+    }
+    Ok(AvalancheReport { rounds })
+}
+
+fn trace(password: &[u8]) -> Result<[[u32; 5]; 80], HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let len = fold_into(password, &mut buffer)?;
+
+    let mut w = load_block(&buffer[..len]);
+    expand(&mut w);
+
+    let mut states = [[0u32; 5]; 80];
+    compress_traced(IV, &w, |round, state| states[round] = state);
+    Ok(states)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::{avalanche, hash_case_variants, raw_state};
+    use crate::{compress_traced, expand, load_block, HashError, IV};
+    use std::fmt::Write;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_raw_state_plus_iv_is_digest() {
+        let raw = raw_state(b"12345").unwrap();
+        let words: Vec<u32> = raw
+            .iter()
+            .zip(IV)
+            .map(|(r, iv)| r.wrapping_add(iv))
+            .collect();
+        assert_eq!(
+            words,
+            [0x460e0af6, 0xc1828a93, 0xfe887cbe, 0x103d6ca6, 0xab97a0e4]
+        );
+    }
+
+    #[test]
+    fn test_raw_state_validates_input() {
+        assert_eq!(raw_state(b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_avalanche_identical_inputs() {
+        let report = avalanche(b"12345", b"12345").unwrap();
+        assert_eq!(report.rounds, [0; 80]);
+        assert_eq!(report.first_divergence(), None);
+
+        // folding happens first, so case-only differences never diverge
+        assert_eq!(avalanche(b"abc", b"ABC").unwrap().first_divergence(), None);
+    }
+
+    #[test]
+    fn test_avalanche_first_word_difference() {
+        // word 0 feeds round 0 directly, so the states split immediately
+        let report = avalanche(b"12345", b"22345").unwrap();
+        assert_eq!(report.first_divergence(), Some(0));
+        assert!(report.rounds[79] > 0);
+
+        // byte 4 lives in word 1, which is first read in round 1
+        let report = avalanche(b"12345", b"12346").unwrap();
+        assert_eq!(report.first_divergence(), Some(1));
+    }
+
+    #[test]
+    fn test_avalanche_validates_both_inputs() {
+        assert_eq!(avalanche(b"", b"a"), Err(HashError::Empty));
+        assert_eq!(avalanche(b"a", b""), Err(HashError::Empty));
+    }
+
+    // Compares `actual` with tests/snapshots/<name>.snap, or rewrites the file
+    // when UPDATE_SNAPSHOTS is set.
+    fn check_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("{}.snap", name));
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "cannot read {}: {}; run with UPDATE_SNAPSHOTS=1 to create it",
+                path.display(),
+                e
+            )
+        });
+
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = actual.lines();
+        for line in 1.. {
+            match (expected_lines.next(), actual_lines.next()) {
+                (None, None) => return,
+                (e, a) if e == a => continue,
+                (e, a) => panic!(
+                    "{} differs at line {}\nexpected: {}\n  actual: {}",
+                    path.display(),
+                    line,
+                    e.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of output>")
+                ),
+            }
+        }
+    }
+
+    fn render_intermediates(input: &[u8]) -> String {
+        let mut w = load_block(input);
+        expand(&mut w);
+
+        let mut out = String::new();
+        writeln!(out, "input: {:?}", String::from_utf8_lossy(input)).unwrap();
+        writeln!(out, "schedule:").unwrap();
+        for (row, words) in w.chunks(8).enumerate() {
+            let words: Vec<String> = words.iter().map(|w| format!("{:08x}", w)).collect();
+            writeln!(out, "{:3}: {}", row * 8, words.join(" ")).unwrap();
+        }
+
+        writeln!(out, "states:").unwrap();
+        compress_traced(IV, &w, |round, state| {
+            if round % 20 == 19 {
+                let state: Vec<String> = state.iter().map(|v| format!("{:08x}", v)).collect();
+                writeln!(out, "after round {}: {}", round, state.join(" ")).unwrap();
+            }
+        });
+        out
+    }
+
+    #[test]
+    fn test_abc_intermediates_snapshot() {
+        check_snapshot("abc", &render_intermediates(b"abc"));
+    }
+
+    #[test]
+    fn test_hash_case_variants() {
+        let variants = hash_case_variants("PvPgN").unwrap();
+        let forms: Vec<&str> = variants.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(forms, ["PvPgN", "pvpgn", "PVPGN"]);
+        assert!(variants.iter().all(|(_, hash)| *hash == variants[0].1));
+
+        // Unicode case mapping would give "STRASSE" and "i\u{307}stanbul" here
+        for password in ["Straße", "İstanbul"] {
+            let variants = hash_case_variants(password).unwrap();
+            assert!(variants.iter().all(|(_, hash)| *hash == variants[0].1));
+        }
+        let forms = hash_case_variants("Straße").unwrap();
+        assert_eq!(forms[2].0, "STRAßE");
+
+        assert_eq!(hash_case_variants(""), Err(HashError::Empty));
+    }
+}
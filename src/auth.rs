@@ -0,0 +1,133 @@
+use crate::{verify, verify_str, Hash, HashError};
+
+/// The result of checking a password against a well-formed stored hash.
+///
+/// A wrong password is an ordinary outcome, not an error; `Err` from
+/// [`authenticate`] is kept for input or storage that could not be checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthOutcome {
+    Match,
+    NoMatch,
+}
+
+/// Checks `password` against `stored_hex` like [`verify_str`], returning
+/// [`AuthOutcome::NoMatch`] for a wrong password and `Err` only when the
+/// password is unusable or the stored hash does not parse.
+pub fn authenticate(password: &str, stored_hex: &str) -> Result<AuthOutcome, HashError> {
+    if verify_str(password, stored_hex)? {
+        Ok(AuthOutcome::Match)
+    } else {
+        Ok(AuthOutcome::NoMatch)
+    }
+}
+
+/// Does the work of [`verify`] against a fixed dummy hash and discards the
+/// result, for the account-not-found branch of a login handler.
+///
+/// Calling this when there is no stored hash makes a missing account take
+/// about as long to reject as a wrong password, so response times do not
+/// reveal which names exist. Errors are the same as for [`verify`].
+pub fn dummy_verify(password: &str) -> Result<(), HashError> {
+    let dummy = Hash::from([0u8; 20]);
+    std::hint::black_box(verify(password, std::hint::black_box(&dummy))?);
+    Ok(())
+}
+
+/// Runs [`authenticate`] on every `(password, stored_hex)` pair and returns
+/// each outcome with its index in `pairs`.
+///
+/// Meant for checking a bulk migration against known passwords. Entries are
+/// independent: a bad password or malformed stored hash only fails its own
+/// entry.
+pub fn verify_batch(pairs: &[(&str, &str)]) -> Vec<(usize, Result<AuthOutcome, HashError>)> {
+    pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (password, stored_hex))| (i, authenticate(password, stored_hex)))
+        .collect()
+}
+
+/// Like [`verify_batch`], spreading the entries over the rayon thread pool.
+/// The result is in the same order as `pairs`.
+#[cfg(feature = "rayon")]
+pub fn verify_batch_par(pairs: &[(&str, &str)]) -> Vec<(usize, Result<AuthOutcome, HashError>)> {
+    use rayon::prelude::*;
+
+    pairs
+        .par_iter()
+        .enumerate()
+        .map(|(i, (password, stored_hex))| (i, authenticate(password, stored_hex)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{authenticate, dummy_verify, verify_batch, AuthOutcome, HashError};
+
+    const STORED: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+
+    #[test]
+    fn test_match() {
+        assert_eq!(authenticate("12345", STORED), Ok(AuthOutcome::Match));
+        assert_eq!(
+            authenticate("12345", &STORED.to_ascii_uppercase()),
+            Ok(AuthOutcome::Match)
+        );
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(authenticate("54321", STORED), Ok(AuthOutcome::NoMatch));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(authenticate("", STORED), Err(HashError::Empty));
+        assert_eq!(
+            authenticate("12345", "460e0af6"),
+            Err(HashError::InvalidHexLength { len: 8 })
+        );
+    }
+
+    #[test]
+    fn test_dummy_verify() {
+        assert_eq!(dummy_verify("12345"), Ok(()));
+        assert_eq!(dummy_verify(""), Err(HashError::Empty));
+        assert_eq!(
+            dummy_verify(&"a".repeat(1025)),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    const BATCH: &[(&str, &str)] = &[
+        ("12345", STORED),
+        ("54321", STORED),
+        ("12345", "not a hash"),
+        ("", STORED),
+        ("Admin", "e9105a5fc971179bd3540a61d3e09aad65bf89d6"),
+    ];
+
+    #[test]
+    fn test_verify_batch() {
+        assert_eq!(
+            verify_batch(BATCH),
+            [
+                (0, Ok(AuthOutcome::Match)),
+                (1, Ok(AuthOutcome::NoMatch)),
+                (2, Err(HashError::InvalidHexLength { len: 10 })),
+                (3, Err(HashError::Empty)),
+                (4, Ok(AuthOutcome::Match)),
+            ]
+        );
+        assert!(verify_batch(&[]).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_verify_batch_par() {
+        assert_eq!(crate::verify_batch_par(BATCH), verify_batch(BATCH));
+
+        let many: Vec<_> = BATCH.iter().copied().cycle().take(500).collect();
+        assert_eq!(crate::verify_batch_par(&many), verify_batch(&many));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{calculate_hash, hash, parse_hash_hex, AuthOutcome, HashError};
+
+/// A password hash scheme that [`detect_algorithm`] can recognise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// The PvPGN broken SHA-1 implemented by this crate.
+    Bsha1,
+    /// Standard SHA-1 of the password bytes as given.
+    Sha1,
+    /// MD5 of the password bytes as given.
+    Md5,
+}
+
+/// Works out which scheme produced `stored_hex` from `password`.
+///
+/// The broken SHA-1 is always tried first. With the `autodetect` feature,
+/// standard SHA-1 is also tried for 40 digit hashes and MD5 for 32 digit ones;
+/// without it, anything but 40 digits is `InvalidHexLength`. Every comparison
+/// is constant time. `Ok(None)` means the hash is well formed but nothing
+/// matched.
+pub fn detect_algorithm(password: &str, stored_hex: &str) -> Result<Option<Algorithm>, HashError> {
+    #[cfg(feature = "autodetect")]
+    if stored_hex.len() == 32 {
+        return Ok(md5_matches(password, stored_hex)?.then_some(Algorithm::Md5));
+    }
+
+    let stored = parse_hash_hex(stored_hex)?;
+    if hash::ct_eq(&calculate_hash(password)?, &stored) {
+        return Ok(Some(Algorithm::Bsha1));
+    }
+
+    #[cfg(feature = "autodetect")]
+    {
+        use sha1::Digest;
+
+        let digest: [u8; 20] = sha1::Sha1::digest(password.as_bytes()).into();
+        if hash::ct_eq_bytes(&digest, stored.as_bytes()) {
+            return Ok(Some(Algorithm::Sha1));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(feature = "autodetect")]
+fn md5_matches(password: &str, stored_hex: &str) -> Result<bool, HashError> {
+    use md5::Digest;
+
+    if password.is_empty() {
+        return Err(HashError::Empty);
+    }
+    let stored: [u8; 16] = hash::decode_hex(stored_hex)?;
+    let digest: [u8; 16] = md5::Md5::digest(password.as_bytes()).into();
+    Ok(hash::ct_eq_bytes(&digest, &stored))
+}
+
+/// Like [`authenticate`](crate::authenticate), accepting any scheme that
+/// [`detect_algorithm`] recognises. Use [`detect_algorithm`] directly to learn
+/// which one matched, e.g. to plan a migration.
+pub fn verify_autodetect(password: &str, stored_hex: &str) -> Result<AuthOutcome, HashError> {
+    match detect_algorithm(password, stored_hex)? {
+        Some(_) => Ok(AuthOutcome::Match),
+        None => Ok(AuthOutcome::NoMatch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{detect_algorithm, verify_autodetect, Algorithm, AuthOutcome, HashError};
+
+    const BSHA1: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+    const SHA1: &str = "8cb2237d0679ca88db6464eac60da96345513964";
+    const MD5: &str = "827ccb0eea8a706c4c34a16891f84e7b";
+
+    #[test]
+    fn test_detects_broken_sha1_only() {
+        assert_eq!(detect_algorithm("12345", BSHA1), Ok(Some(Algorithm::Bsha1)));
+        assert_eq!(
+            detect_algorithm("12345", &BSHA1.to_ascii_uppercase()),
+            Ok(Some(Algorithm::Bsha1))
+        );
+        assert_eq!(verify_autodetect("12345", BSHA1), Ok(AuthOutcome::Match));
+        assert_eq!(verify_autodetect("54321", BSHA1), Ok(AuthOutcome::NoMatch));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(detect_algorithm("", BSHA1), Err(HashError::Empty));
+        assert_eq!(
+            detect_algorithm("12345", "460e"),
+            Err(HashError::InvalidHexLength { len: 4 })
+        );
+    }
+
+    #[cfg(not(feature = "autodetect"))]
+    #[test]
+    fn test_other_schemes_need_feature() {
+        assert_eq!(detect_algorithm("12345", SHA1), Ok(None));
+        assert_eq!(
+            detect_algorithm("12345", MD5),
+            Err(HashError::InvalidHexLength { len: 32 })
+        );
+    }
+
+    #[cfg(feature = "autodetect")]
+    #[test]
+    fn test_other_schemes() {
+        assert_eq!(detect_algorithm("12345", SHA1), Ok(Some(Algorithm::Sha1)));
+        assert_eq!(detect_algorithm("12345", MD5), Ok(Some(Algorithm::Md5)));
+        assert_eq!(
+            detect_algorithm("12345", &MD5.to_ascii_uppercase()),
+            Ok(Some(Algorithm::Md5))
+        );
+        assert_eq!(verify_autodetect("54321", MD5), Ok(AuthOutcome::NoMatch));
+        assert_eq!(detect_algorithm("", MD5), Err(HashError::Empty));
+
+        // SHA-1 is case sensitive, unlike the broken variant
+        assert_eq!(detect_algorithm("ABC", SHA1), Ok(None));
+    }
+}
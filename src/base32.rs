@@ -0,0 +1,123 @@
+//! Crockford base32 encoding of the digest.
+//!
+//! 20 bytes are exactly 32 characters with no padding. Output is uppercase;
+//! decoding ignores case, hyphens, and reads `O` as `0` and `I`/`L` as `1`,
+//! as the Crockford alphabet intends for manually entered identifiers.
+
+use crate::{calculate_hash, hash, Hash, HashError};
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Hashes like [`get_hash_string`](crate::get_hash_string) and encodes the
+/// digest as Crockford base32.
+pub fn get_hash_base32(password: &str) -> Result<String, HashError> {
+    Ok(encode(&calculate_hash(password)?))
+}
+
+/// Verifies `password` in constant time against a base32 hash produced by
+/// [`get_hash_base32`].
+pub fn verify_base32(password: &str, stored: &str) -> Result<bool, HashError> {
+    let stored = decode(stored)?;
+    let hash = calculate_hash(password)?;
+    Ok(hash::ct_eq(&hash, &stored))
+}
+
+/// Encodes `hash` as 32 uppercase Crockford base32 digits.
+pub fn encode(hash: &Hash) -> String {
+    let mut out = String::with_capacity(32);
+    for group in hash.as_bytes().chunks_exact(5) {
+        let bits = group.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        for i in (0..8).rev() {
+            out.push(ALPHABET[(bits >> (i * 5)) as usize & 0x1f] as char);
+        }
+    }
+    out
+}
+
+/// Decodes 32 Crockford base32 digits, ignoring case and hyphens.
+pub fn decode(s: &str) -> Result<Hash, HashError> {
+    let mut bits: u64 = 0;
+    let mut pending = 0;
+    let mut count = 0;
+    let mut bytes = [0u8; 20];
+    let mut out = 0;
+
+    for (index, c) in s.bytes().enumerate() {
+        if c == b'-' {
+            continue;
+        }
+        let value = digit_value(c).ok_or(HashError::InvalidBase32Digit { index })?;
+        count += 1;
+        if count > 32 {
+            continue;
+        }
+
+        bits = (bits << 5) | value as u64;
+        pending += 5;
+        if pending >= 8 {
+            pending -= 8;
+            bytes[out] = (bits >> pending) as u8;
+            out += 1;
+        }
+    }
+
+    if count != 32 {
+        return Err(HashError::InvalidBase32Length { len: count });
+    }
+    Ok(Hash::from(bytes))
+}
+
+fn digit_value(c: u8) -> Option<u8> {
+    match c.to_ascii_uppercase() {
+        b'O' => Some(0),
+        b'I' | b'L' => Some(1),
+        c => ALPHABET.iter().position(|&a| a == c).map(|i| i as u8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base32::{decode, encode, get_hash_base32, verify_base32};
+    use crate::{parse_hash_hex, HashError};
+
+    const BASE32: &str = "8R70NXP1GA597ZM8FJZ10FBCMTNSF874";
+
+    #[test]
+    fn test_known_value() {
+        assert_eq!(get_hash_base32("12345").unwrap(), BASE32);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let hash = parse_hash_hex("460e0af6c1828a93fe887cbe103d6ca6ab97a0e4").unwrap();
+        assert_eq!(decode(&encode(&hash)), Ok(hash));
+        assert_eq!(decode(&BASE32.to_ascii_lowercase()), Ok(hash));
+        assert_eq!(decode("8R70-NXP1-GA59-7ZM8-FJZ1-OFBC-MTNS-F874"), Ok(hash));
+    }
+
+    #[test]
+    fn test_verify_base32() {
+        assert_eq!(verify_base32("12345", BASE32), Ok(true));
+        assert_eq!(
+            verify_base32("12345", &BASE32.to_ascii_lowercase()),
+            Ok(true)
+        );
+        assert_eq!(verify_base32("54321", BASE32), Ok(false));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode(&BASE32[..31]),
+            Err(HashError::InvalidBase32Length { len: 31 })
+        );
+        assert_eq!(
+            decode(&format!("{}0", BASE32)),
+            Err(HashError::InvalidBase32Length { len: 33 })
+        );
+        assert_eq!(
+            decode("U R"),
+            Err(HashError::InvalidBase32Digit { index: 0 })
+        );
+    }
+}
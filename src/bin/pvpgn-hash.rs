@@ -0,0 +1,73 @@
+//! Command line front-end for PvPGN password hashes.
+//!
+//!     pvpgn-hash hash                 read a password from stdin, print its hash
+//!     pvpgn-hash verify <stored_hex>  read a password from stdin, check it
+//!
+//! The password is one line of stdin; only the line terminator is removed, so
+//! leading and trailing spaces are part of the password. `verify` prints
+//! nothing and reports through its exit code: 0 on a match, 1 on a mismatch
+//! and 2 on any error. Every command exits with 2 on a usage error.
+
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: pvpgn-hash hash | pvpgn-hash verify <stored_hex>";
+
+fn read_password() -> io::Result<String> {
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(line)
+}
+
+fn hash() -> ExitCode {
+    let result = read_password()
+        .map_err(pvpgn_hash_rs::HashError::from)
+        .and_then(|password| pvpgn_hash_rs::get_hash_string(&password));
+
+    match result {
+        Ok(hex) => {
+            println!("{}", hex);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("pvpgn-hash: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn verify(stored_hex: &str) -> ExitCode {
+    let result = read_password()
+        .map_err(pvpgn_hash_rs::HashError::from)
+        .and_then(|password| pvpgn_hash_rs::verify_str(&password, stored_hex));
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("pvpgn-hash: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["hash"] => hash(),
+        ["verify", stored_hex] => verify(stored_hex),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
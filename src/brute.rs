@@ -0,0 +1,85 @@
+//! Exhaustive search for recovering a lost password with a known charset.
+//!
+//! The search tries every string over `charset` of length 1, then 2, and so
+//! on, so its cost is `charset.len().pow(max_len)` hashes in the worst case.
+//! That is fine for an admin recovering a short password of their own and
+//! hopeless for anything longer; this is not a general cracking tool.
+
+use crate::{hash_ascii, Hash, MAX_INPUT_LEN};
+
+/// Returns the first candidate over `charset`, shortest first, whose hash is
+/// `expected`. Candidates are hashed like [`hash_bytes_iter`](crate::hash_bytes_iter),
+/// so letter case in `charset` is folded away.
+pub fn brute_find(expected: &Hash, charset: &[u8], max_len: usize) -> Option<Vec<u8>> {
+    if charset.is_empty() {
+        return None;
+    }
+
+    let max_len = max_len.min(MAX_INPUT_LEN);
+    let mut indices: Vec<usize> = Vec::with_capacity(max_len);
+    let mut candidate: Vec<u8> = Vec::with_capacity(max_len);
+
+    for len in 1..=max_len {
+        indices.clear();
+        indices.resize(len, 0);
+        candidate.clear();
+        candidate.resize(len, charset[0]);
+
+        loop {
+            if hash_ascii(&candidate).as_ref() == Ok(expected) {
+                return Some(candidate);
+            }
+            if !advance(&mut indices, &mut candidate, charset) {
+                break;
+            }
+        }
+    }
+
+    None
+}
+
+// Steps the odometer to the next candidate, returning false once every
+// position has wrapped around.
+fn advance(indices: &mut [usize], candidate: &mut [u8], charset: &[u8]) -> bool {
+    for pos in (0..indices.len()).rev() {
+        indices[pos] += 1;
+        if indices[pos] < charset.len() {
+            candidate[pos] = charset[indices[pos]];
+            return true;
+        }
+        indices[pos] = 0;
+        candidate[pos] = charset[0];
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::brute::brute_find;
+    use crate::hash_bytes_iter;
+
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+    #[test]
+    fn test_recovers_two_char_password() {
+        let expected = hash_bytes_iter("z9".bytes()).unwrap();
+        assert_eq!(brute_find(&expected, CHARSET, 3), Some(b"z9".to_vec()));
+    }
+
+    #[test]
+    fn test_gives_up_past_max_len() {
+        let expected = hash_bytes_iter("abc".bytes()).unwrap();
+        assert_eq!(brute_find(&expected, CHARSET, 2), None);
+        assert_eq!(brute_find(&expected, b"", 3), None);
+    }
+
+    #[test]
+    fn test_huge_max_len() {
+        let expected = hash_bytes_iter("ba".bytes()).unwrap();
+        assert_eq!(
+            brute_find(&expected, b"ab", usize::MAX),
+            Some(b"ba".to_vec())
+        );
+        assert_eq!(brute_find(&expected, b"ab", 1 << 40), Some(b"ba".to_vec()));
+    }
+}
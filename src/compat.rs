@@ -0,0 +1,46 @@
+//! The 1.x signatures that reported every failure as `std::io::ErrorKind`.
+//!
+//! These exist so code written against 1.x keeps compiling after an upgrade;
+//! new code should use the [`HashError`](crate::HashError) based functions at
+//! the crate root.
+
+use std::io::ErrorKind;
+
+#[deprecated(note = "use `get_hash_bytes`, which returns `HashError`")]
+pub fn get_hash_bytes_compat(password: Vec<u8>) -> Result<Vec<u8>, ErrorKind> {
+    crate::get_hash_bytes(password).map_err(ErrorKind::from)
+}
+
+#[deprecated(note = "use `get_hash_string`, which returns `HashError`")]
+pub fn get_hash_string_compat(password: &str) -> Result<String, ErrorKind> {
+    crate::get_hash_string(password).map_err(ErrorKind::from)
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use crate::compat::{get_hash_bytes_compat, get_hash_string_compat};
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_empty_password_with_bytes() {
+        let result = get_hash_bytes_compat(vec![]);
+        assert_eq!(result.unwrap_err(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_invalid_utf8_with_bytes() {
+        let result = get_hash_bytes_compat(vec![0xff]);
+        assert_eq!(result.unwrap_err(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_valid_password_with_string() {
+        let result = get_hash_string_compat("12345");
+        assert_eq!(result.unwrap(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+        assert_eq!(
+            get_hash_string_compat(&"a".repeat(1025)),
+            Err(ErrorKind::InvalidData)
+        );
+    }
+}
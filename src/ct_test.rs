@@ -0,0 +1,119 @@
+//! Best-effort timing checks, in the spirit of dudect: `ct_eq` must not leak
+//! how many leading bytes of two hashes match, and `dummy_verify` must take
+//! as long as a real `verify`.
+//!
+//! Timing tests are inherently noisy: a busy machine, frequency scaling or a
+//! coarse clock can all fail this spuriously, which is why it only runs with
+//! `--features ct-test`. Treat a failure as a prompt to rerun on an idle
+//! machine and inspect the generated code, not as proof of a leak.
+
+use crate::hash::ct_eq;
+use crate::{dummy_verify, verify, Hash};
+use std::hint::black_box;
+use std::time::Instant;
+
+const SAMPLES: usize = 300;
+const CALLS_PER_SAMPLE: usize = 64;
+
+fn sample(a: &Hash, b: &Hash) -> f64 {
+    let start = Instant::now();
+    for _ in 0..CALLS_PER_SAMPLE {
+        black_box(ct_eq(black_box(a), black_box(b)));
+    }
+    start.elapsed().as_nanos() as f64
+}
+
+// Mean of the fastest 90% of samples, dropping preemption outliers.
+fn trimmed_mean(samples: &mut [f64]) -> f64 {
+    samples.sort_by(|a, b| a.total_cmp(b));
+    let kept = &samples[..samples.len() * 9 / 10];
+    kept.iter().sum::<f64>() / kept.len() as f64
+}
+
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |s: &[f64]| s.iter().sum::<f64>() / s.len() as f64;
+    let var =
+        |s: &[f64], m: f64| s.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (s.len() - 1) as f64;
+
+    let (ma, mb) = (mean(a), mean(b));
+    let (va, vb) = (var(a, ma), var(b, mb));
+    (ma - mb) / (va / a.len() as f64 + vb / b.len() as f64).sqrt()
+}
+
+fn pearson(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let (mx, my) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+    let cov: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mx) * (y - my)).sum();
+    let sx: f64 = xs.iter().map(|x| (x - mx).powi(2)).sum::<f64>().sqrt();
+    let sy: f64 = ys.iter().map(|y| (y - my).powi(2)).sum::<f64>().sqrt();
+    if sx == 0.0 || sy == 0.0 {
+        return 0.0;
+    }
+    cov / (sx * sy)
+}
+
+#[test]
+fn test_ct_eq_timing_independent_of_matching_prefix() {
+    let stored = Hash::from([0xa5u8; 20]);
+
+    // candidates[k] matches the first k bytes of `stored`
+    let candidates: Vec<Hash> = (0..=20)
+        .map(|k| {
+            let mut bytes = [0x5au8; 20];
+            bytes[..k].copy_from_slice(&[0xa5; 20][..k]);
+            Hash::from(bytes)
+        })
+        .collect();
+
+    // interleave the classes so clock drift affects them all alike
+    let mut timings = vec![Vec::with_capacity(SAMPLES); candidates.len()];
+    for _ in 0..SAMPLES {
+        for (k, candidate) in candidates.iter().enumerate() {
+            timings[k].push(sample(&stored, candidate));
+        }
+    }
+
+    let t = welch_t(&timings[0], &timings[20]);
+    assert!(
+        t.abs() < 10.0,
+        "no-match vs full-match t statistic {:.2}",
+        t
+    );
+
+    let ks: Vec<f64> = (0..=20).map(|k| k as f64).collect();
+    let means: Vec<f64> = timings.iter_mut().map(|s| trimmed_mean(s)).collect();
+    let r = pearson(&ks, &means);
+    assert!(
+        r.abs() < 0.9,
+        "matching prefix correlates with time, r = {:.2}",
+        r
+    );
+}
+
+#[test]
+fn test_dummy_verify_timing_matches_verify() {
+    let stored = Hash::from([0xa5u8; 20]);
+    let time = |f: &dyn Fn()| {
+        let start = Instant::now();
+        for _ in 0..CALLS_PER_SAMPLE {
+            f();
+        }
+        start.elapsed().as_nanos() as f64
+    };
+
+    let mut real = Vec::with_capacity(SAMPLES);
+    let mut dummy = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        real.push(time(&|| {
+            black_box(verify(black_box("hunter2"), &stored).unwrap());
+        }));
+        dummy.push(time(&|| dummy_verify(black_box("hunter2")).unwrap()));
+    }
+
+    let t = welch_t(&real, &dummy);
+    assert!(
+        t.abs() < 10.0,
+        "verify vs dummy_verify t statistic {:.2}",
+        t
+    );
+}
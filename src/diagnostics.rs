@@ -0,0 +1,74 @@
+//! Metadata about how a password was normalized, for debugging hashes that
+//! do not match what a server stored without logging the password itself.
+
+use crate::{digest, fold_into, Hash, HashError, MAX_INPUT_LEN};
+
+/// What folding did to a password. Holds no password bytes, so it is safe to
+/// log; the digest it comes with is not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizedInfo {
+    /// Length in bytes of the folded input that was hashed.
+    pub len: usize,
+    /// Whether folding changed any byte, i.e. the input had `A`-`Z` in it.
+    pub changed_by_fold: bool,
+    /// Whether every byte of the input was ASCII.
+    pub ascii: bool,
+}
+
+/// Hashes `password` like [`hash_bytes_iter`](crate::hash_bytes_iter) and
+/// reports how it was normalized on the way.
+pub fn hash_debug(password: &[u8]) -> Result<(Hash, NormalizedInfo), HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let len = fold_into(password, &mut buffer)?;
+
+    let info = NormalizedInfo {
+        len,
+        changed_by_fold: buffer[..len] != *password,
+        ascii: password.is_ascii(),
+    };
+    Ok((digest(&buffer[..len]), info))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::{hash_debug, NormalizedInfo};
+    use crate::{hash_bytes_iter, HashError};
+
+    #[test]
+    fn test_mixed_case() {
+        let (hash, info) = hash_debug(b"PvPGN 1").unwrap();
+        assert_eq!(hash, hash_bytes_iter("pvpgn 1".bytes()).unwrap());
+        assert_eq!(
+            info,
+            NormalizedInfo {
+                len: 7,
+                changed_by_fold: true,
+                ascii: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unchanged_and_non_ascii() {
+        let (_, info) = hash_debug(b"12345").unwrap();
+        assert!(!info.changed_by_fold);
+
+        // only ASCII folds, so the uppercase `É` is left alone
+        let (_, info) = hash_debug("CAFÉ".as_bytes()).unwrap();
+        assert_eq!(
+            info,
+            NormalizedInfo {
+                len: 5,
+                changed_by_fold: true,
+                ascii: false,
+            }
+        );
+        let (_, info) = hash_debug("É".as_bytes()).unwrap();
+        assert!(!info.changed_by_fold);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(hash_debug(b""), Err(HashError::Empty));
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+use std::io::ErrorKind;
+
+/// Everything that can go wrong while hashing or verifying.
+///
+/// New variants may be added in minor releases, so matches need a wildcard
+/// arm. Code that only cares about the category can match on
+/// [`kind`](HashError::kind) instead of destructuring the fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HashError {
+    /// The input was empty.
+    Empty,
+    /// The input is longer than the 1024 byte hash buffer.
+    TooLong { len: usize },
+    /// The input is shorter than the configured minimum length.
+    TooShort { len: usize, min: usize },
+    /// A byte password given to a `str` based entry point was not UTF-8.
+    InvalidUtf8,
+    /// A hex hash was not exactly 40 characters long.
+    InvalidHexLength { len: usize },
+    /// A hex hash contained a non-hex character at byte offset `index`.
+    InvalidHexDigit { index: usize },
+    /// A base32 hash did not have exactly 32 digits.
+    InvalidBase32Length { len: usize },
+    /// A base32 hash contained a character outside the Crockford alphabet.
+    InvalidBase32Digit { index: usize },
+    /// An account file has no `passhash1` field.
+    AccountFieldMissing,
+    /// An account file's `passhash1` line is not a quoted `"key"="value"` pair.
+    AccountFieldMalformed,
+    /// A requested digest prefix was longer than the 20 byte digest.
+    PrefixTooLong { len: usize },
+    /// A tagged hash named an algorithm this crate does not store.
+    UnknownTag,
+    #[cfg_attr(
+        feature = "ratelimit",
+        doc = "A [`RateLimitedHasher`](crate::ratelimit::RateLimitedHasher) has no"
+    )]
+    #[cfg_attr(not(feature = "ratelimit"), doc = "A `RateLimitedHasher` has no")]
+    /// tokens left for this call.
+    RateLimited,
+    /// Reading the input failed.
+    Io(ErrorKind),
+}
+
+/// The category of a [`HashError`], without its fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashErrorKind {
+    Empty,
+    TooLong,
+    TooShort,
+    InvalidUtf8,
+    InvalidHexLength,
+    InvalidHexDigit,
+    InvalidBase32Length,
+    InvalidBase32Digit,
+    AccountFieldMissing,
+    AccountFieldMalformed,
+    PrefixTooLong,
+    UnknownTag,
+    RateLimited,
+    Io,
+}
+
+impl HashError {
+    /// Returns the category of this error.
+    pub fn kind(&self) -> HashErrorKind {
+        match self {
+            HashError::Empty => HashErrorKind::Empty,
+            HashError::TooLong { .. } => HashErrorKind::TooLong,
+            HashError::TooShort { .. } => HashErrorKind::TooShort,
+            HashError::InvalidUtf8 => HashErrorKind::InvalidUtf8,
+            HashError::InvalidHexLength { .. } => HashErrorKind::InvalidHexLength,
+            HashError::InvalidHexDigit { .. } => HashErrorKind::InvalidHexDigit,
+            HashError::InvalidBase32Length { .. } => HashErrorKind::InvalidBase32Length,
+            HashError::InvalidBase32Digit { .. } => HashErrorKind::InvalidBase32Digit,
+            HashError::AccountFieldMissing => HashErrorKind::AccountFieldMissing,
+            HashError::AccountFieldMalformed => HashErrorKind::AccountFieldMalformed,
+            HashError::PrefixTooLong { .. } => HashErrorKind::PrefixTooLong,
+            HashError::UnknownTag => HashErrorKind::UnknownTag,
+            HashError::RateLimited => HashErrorKind::RateLimited,
+            HashError::Io(_) => HashErrorKind::Io,
+        }
+    }
+}
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashError::Empty => write!(f, "input is empty"),
+            HashError::TooLong { len } => {
+                write!(f, "input is {} bytes, at most 1024 are allowed", len)
+            }
+            HashError::TooShort { len, min } => {
+                write!(f, "input is {} bytes, at least {} are required", len, min)
+            }
+            HashError::InvalidUtf8 => write!(f, "input is not valid UTF-8"),
+            HashError::InvalidHexLength { len } => {
+                write!(f, "hex hash is {} characters, expected 40", len)
+            }
+            HashError::InvalidHexDigit { index } => {
+                write!(f, "invalid hex digit at offset {}", index)
+            }
+            HashError::InvalidBase32Length { len } => {
+                write!(f, "base32 hash has {} digits, expected 32", len)
+            }
+            HashError::InvalidBase32Digit { index } => {
+                write!(f, "invalid base32 digit at offset {}", index)
+            }
+            HashError::AccountFieldMissing => write!(f, "account has no passhash1 field"),
+            HashError::AccountFieldMalformed => {
+                write!(f, "account passhash1 field is malformed")
+            }
+            HashError::PrefixTooLong { len } => {
+                write!(f, "prefix of {} bytes requested, the digest has 20", len)
+            }
+            HashError::UnknownTag => write!(f, "unknown hash algorithm tag"),
+            HashError::RateLimited => write!(f, "too many hashes, try again later"),
+            HashError::Io(kind) => write!(f, "I/O error: {}", kind),
+        }
+    }
+}
+
+impl std::error::Error for HashError {}
+
+impl From<std::io::Error> for HashError {
+    fn from(e: std::io::Error) -> Self {
+        HashError::Io(e.kind())
+    }
+}
+
+// Every failure used to be reported as `InvalidData`, so that is all the old
+// signatures can distinguish.
+impl From<HashError> for ErrorKind {
+    fn from(_: HashError) -> Self {
+        ErrorKind::InvalidData
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_hash_string, parse_hash_hex, HashError, HashErrorKind};
+
+    #[test]
+    fn test_kind() {
+        let too_long = "a".repeat(1025);
+        assert_eq!(
+            get_hash_string("").unwrap_err().kind(),
+            HashErrorKind::Empty
+        );
+        assert_eq!(
+            get_hash_string(&too_long).unwrap_err().kind(),
+            HashErrorKind::TooLong
+        );
+        assert_eq!(
+            parse_hash_hex("abc").unwrap_err().kind(),
+            HashErrorKind::InvalidHexLength
+        );
+
+        let err = get_hash_string(&too_long).unwrap_err();
+        let message = match err.kind() {
+            HashErrorKind::Empty | HashErrorKind::TooLong => "bad password length",
+            _ => "other",
+        };
+        assert_eq!(message, "bad password length");
+    }
+
+    #[test]
+    fn test_io_kind() {
+        let err = HashError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert_eq!(err, HashError::Io(std::io::ErrorKind::NotFound));
+        assert_eq!(err.kind(), HashErrorKind::Io);
+    }
+}
@@ -0,0 +1,401 @@
+use crate::{Algorithm, HashError};
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// A 20 byte digest, normally broken SHA-1.
+///
+/// Everything in this crate that produces a `Hash` produces broken SHA-1,
+/// except [`parse_tagged`], which also returns a standard SHA-1 digest in
+/// this type for a `sha1:` tag. Keep the [`Algorithm`] it returns alongside
+/// such a value, since comparing it with a broken SHA-1 digest is meaningless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hash([u8; 20]);
+
+impl Hash {
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> [u8; 20] {
+        self.0
+    }
+
+    /// Writes the 20 raw digest bytes to `w`.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.0)
+    }
+
+    /// Writes the 40 lowercase hex characters to `w` without allocating.
+    pub fn write_hex_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.to_hex_bytes())
+    }
+
+    /// Formats the hash as `bsha1:<hex>`, the tagged form read by
+    /// [`parse_tagged`].
+    pub fn to_tagged(&self) -> String {
+        format!("bsha1:{}", self)
+    }
+
+    /// The first 8 hex characters, for correlating log lines that involve the
+    /// same credential. This is a truncation, not a digest of its own, and
+    /// should only ever be used for log correlation.
+    pub fn fingerprint(&self) -> String {
+        let hex = self.to_hex_bytes();
+        hex[..8].iter().map(|&c| c as char).collect()
+    }
+
+    /// Returns the 40 lowercase hex characters as ASCII bytes on the stack.
+    pub fn to_hex_bytes(&self) -> [u8; 40] {
+        let mut out = [0u8; 40];
+        self.to_hex_lower_into(&mut out);
+        out
+    }
+
+    /// Writes the 40 lowercase hex characters into `out` as ASCII bytes.
+    pub fn to_hex_lower_into(&self, out: &mut [u8; 40]) {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        for (pair, b) in out.chunks_exact_mut(2).zip(self.0) {
+            pair[0] = DIGITS[(b >> 4) as usize];
+            pair[1] = DIGITS[(b & 0x0f) as usize];
+        }
+    }
+}
+
+pub(crate) fn ct_eq(a: &Hash, b: &Hash) -> bool {
+    ct_eq_bytes(&a.0, &b.0)
+}
+
+// Compares two equal-length digests without short-circuiting on the first
+// differing byte. With the `subtle` feature the comparison is delegated to
+// `subtle`, whose `Choice` is built to resist being turned back into a branch
+// by the optimizer; otherwise it is a plain XOR-OR fold.
+#[cfg(feature = "subtle")]
+pub(crate) fn ct_eq_bytes<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    use subtle::ConstantTimeEq;
+    a[..].ct_eq(&b[..]).into()
+}
+
+#[cfg(not(feature = "subtle"))]
+pub(crate) fn ct_eq_bytes<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    diff == 0
+}
+
+// `Hash` is `Copy`, so this only clears the one copy it is called on.
+// `secrecy::Secret` needs this too, without the rest of the `zeroize` feature.
+#[cfg(any(feature = "zeroize", feature = "secrecy"))]
+impl zeroize::Zeroize for Hash {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// Lets `Secret<Hash>` print as a redacted placeholder.
+#[cfg(feature = "secrecy")]
+impl secrecy::DebugSecret for Hash {}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for Hash {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl From<[u8; 20]> for Hash {
+    fn from(bytes: [u8; 20]) -> Self {
+        Hash(bytes)
+    }
+}
+
+impl From<Hash> for [u8; 20] {
+    fn from(hash: Hash) -> Self {
+        hash.0
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = self.to_hex_bytes();
+        f.write_str(std::str::from_utf8(&hex).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// The five big-endian words of a [`Hash`](struct@Hash), named after the SHA-1 state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashWords {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+    pub e: u32,
+}
+
+impl From<Hash> for HashWords {
+    fn from(hash: Hash) -> Self {
+        let word = |i: usize| {
+            let b = &hash.0[i * 4..i * 4 + 4];
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        };
+        HashWords {
+            a: word(0),
+            b: word(1),
+            c: word(2),
+            d: word(3),
+            e: word(4),
+        }
+    }
+}
+
+impl From<HashWords> for Hash {
+    fn from(words: HashWords) -> Self {
+        let mut bytes = [0u8; 20];
+        for (chunk, word) in bytes
+            .chunks_exact_mut(4)
+            .zip([words.a, words.b, words.c, words.d, words.e])
+        {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        Hash(bytes)
+    }
+}
+
+impl FromStr for Hash {
+    type Err = HashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_hash_hex(s)
+    }
+}
+
+/// Parses a 40 character hex string, in either letter case, into a [`Hash`](struct@Hash).
+pub fn parse_hash_hex(s: &str) -> Result<Hash, HashError> {
+    decode_hex(s).map(Hash)
+}
+
+/// Parses a stored hash in `<tag>:<hex>` form, where the tag is `bsha1` or
+/// `sha1`, or untagged legacy hex, which is taken to be `bsha1`.
+///
+/// Tags are matched exactly, and any other tag fails with `UnknownTag`. MD5
+/// has no tag because its 16 byte digest does not fit a [`Hash`](struct@Hash).
+/// For `sha1` the returned digest is standard SHA-1, not broken SHA-1.
+pub fn parse_tagged(s: &str) -> Result<(Algorithm, Hash), HashError> {
+    let Some((tag, hex)) = s.split_once(':') else {
+        return Ok((Algorithm::Bsha1, parse_hash_hex(s)?));
+    };
+
+    let algorithm = match tag {
+        "bsha1" => Algorithm::Bsha1,
+        "sha1" => Algorithm::Sha1,
+        _ => return Err(HashError::UnknownTag),
+    };
+    Ok((algorithm, parse_hash_hex(hex)?))
+}
+
+// Decodes exactly `2 * N` hex digits, in either letter case.
+pub(crate) fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N], HashError> {
+    let hex = s.as_bytes();
+    if hex.len() != N * 2 {
+        return Err(HashError::InvalidHexLength { len: hex.len() });
+    }
+
+    let mut bytes = [0u8; N];
+    for (i, pair) in hex.chunks_exact(2).enumerate() {
+        let hi = hex_value(pair[0]).ok_or(HashError::InvalidHexDigit { index: i * 2 })?;
+        let lo = hex_value(pair[1]).ok_or(HashError::InvalidHexDigit { index: i * 2 + 1 })?;
+        bytes[i] = (hi << 4) | lo;
+    }
+    Ok(bytes)
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_hash_hex, parse_tagged, Algorithm, Hash, HashError, HashWords};
+
+    const HEX: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+
+    #[test]
+    fn test_parse_round_trip() {
+        let hash = parse_hash_hex(HEX).unwrap();
+        assert_eq!(hash.to_string(), HEX);
+        assert_eq!(HEX.parse::<Hash>(), Ok(hash));
+    }
+
+    #[test]
+    fn test_parse_uppercase() {
+        let upper = HEX.to_ascii_uppercase();
+        assert_eq!(parse_hash_hex(&upper), parse_hash_hex(HEX));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_hash_hex(&HEX[..39]),
+            Err(HashError::InvalidHexLength { len: 39 })
+        );
+        assert_eq!(
+            parse_hash_hex(""),
+            Err(HashError::InvalidHexLength { len: 0 })
+        );
+
+        let mut bad = HEX.to_string();
+        bad.replace_range(7..8, "g");
+        assert_eq!(
+            parse_hash_hex(&bad),
+            Err(HashError::InvalidHexDigit { index: 7 })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_multibyte() {
+        let s = format!("{}é", &HEX[..38]);
+        assert_eq!(s.len(), 40);
+        assert_eq!(
+            parse_hash_hex(&s),
+            Err(HashError::InvalidHexDigit { index: 38 })
+        );
+    }
+
+    #[test]
+    fn test_hash_words_round_trip() {
+        let hash = parse_hash_hex(HEX).unwrap();
+        let words = HashWords::from(hash);
+        assert_eq!(
+            words,
+            HashWords {
+                a: 0x460e0af6,
+                b: 0xc1828a93,
+                c: 0xfe887cbe,
+                d: 0x103d6ca6,
+                e: 0xab97a0e4,
+            }
+        );
+        assert_eq!(Hash::from(words), hash);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let hash = parse_hash_hex(HEX).unwrap();
+
+        let mut raw = Vec::new();
+        hash.write_to(&mut raw).unwrap();
+        assert_eq!(raw, hash.as_bytes());
+
+        let mut hex = Vec::new();
+        hash.write_hex_to(&mut hex).unwrap();
+        hash.write_hex_to(&mut hex).unwrap();
+        assert_eq!(hex, format!("{}{}", HEX, HEX).as_bytes());
+    }
+
+    #[test]
+    fn test_to_hex_bytes() {
+        let hash = parse_hash_hex(HEX).unwrap();
+        assert_eq!(&hash.to_hex_bytes(), HEX.as_bytes());
+
+        let mut out = [b'!'; 40];
+        hash.to_hex_lower_into(&mut out);
+        assert_eq!(&out, HEX.as_bytes());
+
+        let zero = Hash::from([0; 20]).to_hex_bytes();
+        assert_eq!(&zero, &[b'0'; 40]);
+        let ones = Hash::from([0xff; 20]).to_hex_bytes();
+        assert_eq!(&ones, &[b'f'; 40]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = parse_hash_hex(HEX).unwrap().fingerprint();
+        assert_eq!(fingerprint.len(), 8);
+        assert_eq!(fingerprint, &HEX[..8]);
+    }
+
+    #[test]
+    fn test_tagged_round_trip() {
+        let hash = parse_hash_hex(HEX).unwrap();
+        let tagged = hash.to_tagged();
+        assert_eq!(tagged, format!("bsha1:{}", HEX));
+        assert_eq!(parse_tagged(&tagged), Ok((Algorithm::Bsha1, hash)));
+
+        let sha1 = "sha1:8cb2237d0679ca88db6464eac60da96345513964";
+        assert_eq!(parse_tagged(sha1).unwrap().0, Algorithm::Sha1);
+    }
+
+    #[test]
+    fn test_parse_untagged_is_bsha1() {
+        let hash = parse_hash_hex(HEX).unwrap();
+        assert_eq!(parse_tagged(HEX), Ok((Algorithm::Bsha1, hash)));
+        assert_eq!(
+            parse_tagged(&HEX.to_ascii_uppercase()),
+            Ok((Algorithm::Bsha1, hash))
+        );
+    }
+
+    #[test]
+    fn test_parse_tagged_errors() {
+        let md5 = "md5:827ccb0eea8a706c4c34a16891f84e7b";
+        assert_eq!(parse_tagged(md5), Err(HashError::UnknownTag));
+        assert_eq!(
+            parse_tagged(&format!("BSHA1:{}", HEX)),
+            Err(HashError::UnknownTag)
+        );
+        assert_eq!(
+            parse_tagged("bsha1:460e"),
+            Err(HashError::InvalidHexLength { len: 4 })
+        );
+        assert_eq!(parse_tagged(":"), Err(HashError::UnknownTag));
+    }
+
+    #[test]
+    fn test_ct_eq_known_vector() {
+        let a = parse_hash_hex(HEX).unwrap();
+        let mut bytes = a.into_bytes();
+        assert!(crate::hash::ct_eq(&a, &Hash::from(bytes)));
+
+        for i in [0, 19] {
+            bytes[i] ^= 1;
+            assert!(!crate::hash::ct_eq(&a, &Hash::from(bytes)));
+            bytes[i] ^= 1;
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_subtle_agrees_with_fold() {
+        use subtle::ConstantTimeEq;
+
+        let fold = |a: &Hash, b: &Hash| {
+            a.as_bytes()
+                .iter()
+                .zip(b.as_bytes())
+                .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+                == 0
+        };
+
+        let a = parse_hash_hex(HEX).unwrap();
+        let mut flipped = a.into_bytes();
+        flipped[7] ^= 0x80;
+        for b in [a, Hash::from(flipped), Hash::from([0; 20])] {
+            assert_eq!(bool::from(a.ct_eq(&b)), fold(&a, &b));
+            assert_eq!(crate::hash::ct_eq(&a, &b), fold(&a, &b));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{hash, hash_ascii, Hash, HashError};
+
+/// A password hashing scheme, for code that supports more than one backend.
+pub trait PasswordHasher {
+    /// Hashes `password` as this scheme stores it.
+    fn hash(&self, password: &[u8]) -> Result<Hash, HashError>;
+
+    /// Hashes `password` and compares it against `stored` in constant time.
+    fn verify(&self, password: &[u8], stored: &Hash) -> Result<bool, HashError> {
+        let hash = self.hash(password)?;
+        Ok(hash::ct_eq(&hash, stored))
+    }
+}
+
+/// The stock PvPGN broken SHA-1, folding ASCII case like
+/// [`hash_bytes_iter`](crate::hash_bytes_iter).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PvpgnBsha1;
+
+impl PasswordHasher for PvpgnBsha1 {
+    fn hash(&self, password: &[u8]) -> Result<Hash, HashError> {
+        hash_ascii(password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Hash, HashError, PasswordHasher, PvpgnBsha1};
+
+    const HEX: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+
+    // stands in for a real backend in tests of generic code
+    struct Fixed(Hash);
+
+    impl PasswordHasher for Fixed {
+        fn hash(&self, _password: &[u8]) -> Result<Hash, HashError> {
+            Ok(self.0)
+        }
+    }
+
+    fn login<H: PasswordHasher>(hasher: &H, password: &[u8], stored: &Hash) -> bool {
+        hasher.verify(password, stored).unwrap_or(false)
+    }
+
+    #[test]
+    fn test_pvpgn_bsha1() {
+        let stored: Hash = HEX.parse().unwrap();
+        assert_eq!(PvpgnBsha1.hash(b"12345"), Ok(stored));
+        assert_eq!(PvpgnBsha1.verify(b"12345", &stored), Ok(true));
+        assert_eq!(PvpgnBsha1.verify(b"54321", &stored), Ok(false));
+        assert_eq!(PvpgnBsha1.verify(b"", &stored), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_generic_over_hashers() {
+        let stored: Hash = HEX.parse().unwrap();
+        assert!(login(&PvpgnBsha1, b"12345", &stored));
+        assert!(!login(&PvpgnBsha1, b"", &stored));
+
+        assert!(login(&Fixed(stored), b"anything", &stored));
+        let dyn_hasher: &dyn PasswordHasher = &Fixed(Hash::from([0; 20]));
+        assert_eq!(dyn_hasher.verify(b"12345", &stored), Ok(false));
+    }
+}
@@ -1,75 +1,613 @@
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
-use std::io::{Cursor, Error, ErrorKind, Seek, SeekFrom, Write};
+//! Battle.net v1.0 "broken SHA-1" password hashing as used by PvPGN.
+//!
+//! # Chaining value
+//!
+//! The internal state behaves like a SHA-1 chaining value, and letting callers
+//! seed it would make length-extension against naive salting schemes trivial.
+//! No public function or constructor accepts an initial `[u32; 5]` state, and
+//! the block function stays private:
+//!
+//! ```compile_fail
+//! let mut state = [0u32; 5];
+//! pvpgn_hash_rs::process_block(&mut state, &[0u32; 256]);
+//! ```
+//!
+//! ```compile_fail
+//! let hasher = pvpgn_hash_rs::BrokenSha1::with_state([0u32; 5]);
+//! ```
+//!
+//! ```compile_fail
+//! let hasher = pvpgn_hash_rs::BrokenSha1::from([0u32; 5]);
+//! ```
 
-pub fn get_hash_bytes(password: Vec<u8>) -> Result<Vec<u8>, ErrorKind> {
+mod account;
+#[cfg(feature = "audit")]
+pub mod audit;
+mod auth;
+mod autodetect;
+#[cfg(feature = "base32")]
+pub mod base32;
+#[cfg(feature = "brute")]
+pub mod brute;
+pub mod compat;
+#[cfg(all(test, feature = "ct-test"))]
+mod ct_test;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+mod error;
+mod hash;
+mod hasher;
+mod options;
+#[cfg(feature = "ratelimit")]
+pub mod ratelimit;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod srp;
+mod stream;
+mod weak;
+
+pub use account::{export_passhashes, export_passhashes_skipping, verify_in_account};
+#[cfg(feature = "rayon")]
+pub use auth::verify_batch_par;
+pub use auth::{authenticate, dummy_verify, verify_batch, AuthOutcome};
+pub use autodetect::{detect_algorithm, verify_autodetect, Algorithm};
+pub use error::{HashError, HashErrorKind};
+pub use hash::{parse_hash_hex, parse_tagged, Hash, HashWords};
+pub use hasher::{PasswordHasher, PvpgnBsha1};
+pub use options::{hash_with, ByteOrder, HashOptions};
+pub use stream::BrokenSha1;
+pub use weak::WeakPasswordSet;
+
+const MAX_INPUT_LEN: usize = 1024;
+
+const IV: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// Lowercases `password` with Unicode rules and returns the 20 digest bytes.
+///
+/// Unicode lowercasing needs a `str`, so the bytes must be valid UTF-8 or the
+/// call fails with `InvalidUtf8`. Binary input belongs in
+/// [`get_hash_bytes_raw`].
+pub fn get_hash_bytes(password: Vec<u8>) -> Result<Vec<u8>, HashError> {
     let str_result = match std::str::from_utf8(&password) {
         Ok(s) => s,
-        Err(_) => return Err(ErrorKind::InvalidData),
+        Err(_) => return Err(HashError::InvalidUtf8),
     };
-    calculate_hash(&str_result)
+    let hash = calculate_hash(str_result)?;
+    Ok(hash.as_bytes().to_vec())
+}
+
+/// Hashes arbitrary bytes, returning the 20 digest bytes.
+///
+/// Bytes are hashed as-is apart from folding `A`-`Z`, and are never checked
+/// for UTF-8. For ASCII input this matches [`get_hash_bytes`]; non-ASCII
+/// letters are not lowercased.
+pub fn get_hash_bytes_raw(password: &[u8]) -> Result<Vec<u8>, HashError> {
+    Ok(hash_ascii(password)?.as_bytes().to_vec())
 }
 
-pub fn get_hash_string(password: &str) -> Result<String, ErrorKind> {
-    let bytes = match calculate_hash(password) {
-        Ok(data) => data,
-        Err(_) => return Err(ErrorKind::InvalidData),
+/// Lowercases `password` with Unicode rules and returns its hash as hex.
+///
+/// The 1024 byte limit applies to the lowercased UTF-8, since that is what
+/// fills the hash buffer; a few characters such as `İ` grow when lowercased.
+pub fn get_hash_string(password: &str) -> Result<String, HashError> {
+    Ok(calculate_hash(password)?.to_string())
+}
+
+/// Hashes like [`get_hash_string`] and splits the hex into runs of `group`
+/// characters joined by `sep`. The last run may be shorter, and no separator
+/// is added at either end. A `group` of 0 yields plain hex.
+pub fn get_hash_grouped(password: &str, group: usize, sep: char) -> Result<String, HashError> {
+    let hex = calculate_hash(password)?.to_hex_bytes();
+    Ok(group_hex(&hex, group, sep))
+}
+
+/// Hashes like [`get_hash_string`] and formats the digest as uppercase hex
+/// bytes separated by colons, e.g. `46:0E:0A:...`.
+pub fn get_hash_colon_upper(password: &str) -> Result<String, HashError> {
+    let mut hex = calculate_hash(password)?.to_hex_bytes();
+    hex.make_ascii_uppercase();
+    Ok(group_hex(&hex, 2, ':'))
+}
+
+fn group_hex(hex: &[u8; 40], group: usize, sep: char) -> String {
+    let runs = if group == 0 {
+        1
+    } else {
+        hex.len().div_ceil(group)
     };
+    let mut grouped = String::with_capacity(hex.len() + (runs - 1) * sep.len_utf8());
+    for (i, &c) in hex.iter().enumerate() {
+        if group > 0 && i > 0 && i % group == 0 {
+            grouped.push(sep);
+        }
+        grouped.push(c as char);
+    }
+    grouped
+}
+
+/// Hashes like [`get_hash_string`] and wraps the digest in a
+/// [`secrecy::Secret`], whose `Debug` output is redacted and which is wiped on
+/// drop. The digest is a password verifier and should not end up in logs.
+#[cfg(feature = "secrecy")]
+pub fn get_hash_secret(password: &str) -> Result<secrecy::Secret<Hash>, HashError> {
+    calculate_hash(password).map(secrecy::Secret::new)
+}
+
+/// Hashes an account name the way PvPGN does for name-keyed lookups.
+///
+/// The normalization is exactly that of [`get_hash_string`]: Unicode
+/// lowercasing, then the same empty and 1024 byte checks on the lowercased
+/// bytes. This function exists so that tooling says which kind of value it is
+/// hashing; usernames are not folded any differently from passwords.
+pub fn hash_username(name: &str) -> Result<Hash, HashError> {
+    calculate_hash(name)
+}
+
+/// Hashes `password` like [`get_hash_string`] and compares it against
+/// `stored` in constant time.
+pub fn verify(password: &str, stored: &Hash) -> Result<bool, HashError> {
+    let hash = calculate_hash(password)?;
+    Ok(hash::ct_eq(&hash, stored))
+}
+
+/// Like [`verify`], with the stored hash given as hex in either letter case.
+pub fn verify_str(password: &str, stored_hex: &str) -> Result<bool, HashError> {
+    let stored = parse_hash_hex(stored_hex)?;
+    verify(password, &stored)
+}
+
+/// Verifies against a stored hex hash whose letter case is not known.
+///
+/// Case is handled while parsing `stored_hex`, and the decoded bytes are then
+/// compared in constant time. Comparing hex strings with
+/// `eq_ignore_ascii_case` instead would leak how many leading characters
+/// match. This is the same check as [`verify_str`], named for call sites that
+/// want the case-insensitivity to be explicit.
+pub fn verify_hex_ci(password: &str, stored_hex: &str) -> Result<bool, HashError> {
+    verify_str(password, stored_hex)
+}
 
-    let hex_string: String = bytes
+/// Hashes every password like [`get_hash_string`], returning one lowercase hex
+/// string or error per entry. A failing entry does not stop the batch.
+pub fn hash_all_hex(passwords: &[&str]) -> Vec<Result<String, HashError>> {
+    let mut lowered = String::new();
+
+    passwords
         .iter()
-        .map(|b| format!("{:02x}", b))
-        .collect::<Vec<String>>()
-        .join("");
+        .map(|password| {
+            lowercase_into(password, &mut lowered);
+            hash_lowered(lowered.as_bytes()).map(|hash| hash.to_string())
+        })
+        .collect()
+}
+
+/// Hashes the bytes yielded by `iter`, folding ASCII case as they arrive.
+///
+/// At most 1024 bytes are consumed; if the iterator yields a 1025th byte the
+/// call fails with `TooLong { len: 1025 }` without draining the rest.
+pub fn hash_bytes_iter<I: Iterator<Item = u8>>(iter: I) -> Result<Hash, HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let mut len = 0;
+
+    for b in iter {
+        if len == MAX_INPUT_LEN {
+            return Err(HashError::TooLong { len: len + 1 });
+        }
+        buffer[len] = b.to_ascii_lowercase();
+        len += 1;
+    }
+
+    check_len(len)?;
+    Ok(digest(&buffer[..len]))
+}
+
+/// Reads a password from `r` and hashes it like [`hash_bytes_iter`].
+///
+/// At most 1024 bytes are consumed. If the reader still has data after that,
+/// the call fails with `TooLong { len: 1025 }` and the pending bytes are left
+/// in the reader, found by peeking with `fill_buf` rather than reading them.
+/// Read errors other than `Interrupted` are returned as `Io`.
+pub fn hash_bufread<R: std::io::BufRead>(r: &mut R) -> Result<Hash, HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let mut len = 0;
+
+    loop {
+        let available = match r.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if available.is_empty() {
+            break;
+        }
+        if len == MAX_INPUT_LEN {
+            return Err(HashError::TooLong { len: len + 1 });
+        }
+
+        let n = available.len().min(MAX_INPUT_LEN - len);
+        for (dst, src) in buffer[len..len + n].iter_mut().zip(available) {
+            *dst = src.to_ascii_lowercase();
+        }
+        r.consume(n);
+        len += n;
+    }
+
+    check_len(len)?;
+    Ok(digest(&buffer[..len]))
+}
+
+/// Hashes `password` like [`hash_bytes_iter`] and reports how long the core
+/// computation took, excluding input validation and case folding.
+///
+/// The resolution of the returned `Duration` depends on the platform clock
+/// behind `Instant`; very short hashes may read as zero on coarse clocks.
+#[cfg(feature = "instrumented")]
+pub fn hash_timed(password: &[u8]) -> Result<(Hash, std::time::Duration), HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let len = fold_into(password, &mut buffer)?;
+
+    let start = std::time::Instant::now();
+    let hash = digest(&buffer[..len]);
+    Ok((hash, start.elapsed()))
+}
+
+/// Computes `hash(hex(hash(password)))`, folding ASCII case on `password`.
+///
+/// The intermediate value is the 40 byte lowercase hex string of the first
+/// digest, as printed by `Display` for [`Hash`](struct@Hash); an uppercase intermediate
+/// would give a different result.
+pub fn double_hash_hex(password: &[u8]) -> Result<Hash, HashError> {
+    let inner = hash_ascii(password)?;
+    Ok(digest(inner.to_string().as_bytes()))
+}
+
+/// Returns the first four digest bytes of `password`, read big-endian, for
+/// handshakes that send them as a quick checksum. ASCII case is folded as for
+/// [`hash_bytes_iter`].
+///
+/// This is a pre-filter, not a security check: 32 bits are easy to collide,
+/// so a matching checksum must still be followed by a full [`verify`].
+pub fn hash_checksum(password: &[u8]) -> Result<u32, HashError> {
+    let b = hash_ascii(password)?.into_bytes();
+    Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Returns true when `a` and `b` are different byte strings with the same
+/// digest, hashing both like [`hash_bytes_iter`].
+///
+/// Inputs that differ only in ASCII letter case count as colliding, since the
+/// fold is part of the hash. Identical inputs do not. This only checks a
+/// given pair; searching for collisions is left to the `brute` and `audit`
+/// tools.
+pub fn passwords_collide(a: &[u8], b: &[u8]) -> Result<bool, HashError> {
+    let (hash_a, hash_b) = (hash_ascii(a)?, hash_ascii(b)?);
+    Ok(a != b && hash_a == hash_b)
+}
+
+/// Hashes `a` and `b` like [`hash_bytes_iter`] and returns how many of the
+/// 160 digest bits differ.
+///
+/// Unrelated inputs land near 80. Inputs that fold to the same bytes, or
+/// that only differ past the first 64 bytes, give 0.
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> Result<u32, HashError> {
+    let (hash_a, hash_b) = (hash_ascii(a)?, hash_ascii(b)?);
+    Ok(hash_a
+        .as_bytes()
+        .iter()
+        .zip(hash_b.as_bytes())
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum())
+}
+
+/// Hashes `password` once, folding ASCII case as for [`hash_bytes_iter`],
+/// and returns the first `len` digest bytes for each entry of `lens`.
+///
+/// Every length is checked before hashing; one over 20 fails the whole call
+/// with `PrefixTooLong`.
+pub fn hash_prefixes(password: &[u8], lens: &[usize]) -> Result<Vec<Vec<u8>>, HashError> {
+    if let Some(&len) = lens.iter().find(|&&len| len > 20) {
+        return Err(HashError::PrefixTooLong { len });
+    }
+
+    let hash = hash_ascii(password)?;
+    Ok(lens
+        .iter()
+        .map(|&len| hash.as_bytes()[..len].to_vec())
+        .collect())
+}
+
+/// Hashes `input` like [`hash_bytes_iter`], using `scratch` as the working
+/// block instead of a stack array.
+///
+/// `scratch` may hold anything on entry; it is cleared before the input is
+/// loaded, and holds the expanded block afterwards. Nothing is allocated.
+pub fn hash_with_scratch(input: &[u8], scratch: &mut [u32; 256]) -> Result<Hash, HashError> {
+    check_len(input.len())?;
+
+    scratch.fill(0);
+    for (word, chunk) in scratch.iter_mut().zip(input.chunks(4)) {
+        let mut bytes = [0u8; 4];
+        for (dst, src) in bytes.iter_mut().zip(chunk) {
+            *dst = src.to_ascii_lowercase();
+        }
+        *word = u32::from_le_bytes(bytes);
+    }
+
+    expand(scratch);
+    let mut state = IV;
+    compress_into(&mut state, scratch);
+    Ok(Hash::from(state_bytes(state)))
+}
+
+/// Hashes a password given as UTF-16 code units, as sent by clients that use
+/// UTF-16LE on the wire.
+///
+/// Code units in `A`-`Z` are lowercased first, then every unit is written as
+/// two little-endian bytes and those bytes are hashed. Folding per code unit
+/// rather than per byte matters: a byte-wise fold would also rewrite the high
+/// byte of units such as `U+4100`. At most 512 units fit the 1024 byte cap.
+pub fn hash_utf16le(units: &[u16]) -> Result<Hash, HashError> {
+    check_len(units.len() * 2)?;
+
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    for (dst, &unit) in buffer.chunks_exact_mut(2).zip(units) {
+        let unit = match unit {
+            0x41..=0x5a => unit + 0x20,
+            _ => unit,
+        };
+        dst.copy_from_slice(&unit.to_le_bytes());
+    }
+    Ok(digest(&buffer[..units.len() * 2]))
+}
+
+/// Hashes `buf` like [`hash_bytes_iter`], folding ASCII case in place instead
+/// of into a copy.
+///
+/// The contents of `buf` are modified: after the call it holds the folded
+/// password. With the `zeroize` feature it is then wiped and left empty,
+/// whether or not hashing succeeded.
+// Takes a `Vec` so that zeroizing can also truncate it.
+#[cfg_attr(not(feature = "zeroize"), allow(clippy::ptr_arg))]
+pub fn hash_in_place(buf: &mut Vec<u8>) -> Result<Hash, HashError> {
+    let result = check_len(buf.len()).map(|()| {
+        buf.make_ascii_lowercase();
+        digest(buf)
+    });
+
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(buf);
+
+    result
+}
+
+/// Hashes `input` without any case folding. The caller guarantees the bytes
+/// are already ASCII-lowercased; debug builds assert that no `A`-`Z` byte is
+/// present. Length is checked as for every other entry point.
+pub fn hash_prelowered(input: &[u8]) -> Result<Hash, HashError> {
+    debug_assert!(
+        !input.iter().any(u8::is_ascii_uppercase),
+        "hash_prelowered called with ASCII uppercase input"
+    );
+    check_len(input.len())?;
+    Ok(digest(input))
+}
+
+/// Hashes a password received as Windows-1252 bytes, as sent by legacy
+/// clients on Western locales.
+///
+/// Stock PvPGN never decodes the password: it runs C `tolower` over the raw
+/// bytes in the "C" locale, which only touches `A`-`Z`, and hashes the
+/// result. This does the same, so accented letters keep their case, `É`
+/// (0xC9) and `é` (0xE9) hash differently, and the digest differs from
+/// hashing the UTF-8 form of the same text. That is an assumption about the
+/// server's locale; a server run under a Latin-1 locale would also fold
+/// 0xC0-0xDE.
+#[cfg(feature = "encodings")]
+pub fn hash_cp1252(bytes: &[u8]) -> Result<Hash, HashError> {
+    hash_ascii(bytes)
+}
+
+/// Maps the file at `path` and hashes its bytes as-is, without case folding,
+/// for pre-lowercased blobs that are not worth reading onto the heap.
+///
+/// The contents go through [`hash_prelowered`], so the same promise applies:
+/// the file must not contain `A`-`Z`, and debug builds assert that.
+///
+/// There is no multi-block mode, so files over the 1024 byte cap fail with
+/// `TooLong` like any other input, and empty files with `Empty`.
+///
+/// Memory mapping assumes nothing truncates or rewrites the file while it is
+/// mapped. If another process does, the mapped bytes can change underneath
+/// the hash or the read can fault; only use this on files you control.
+#[cfg(feature = "mmap")]
+pub fn hash_mmap(path: &std::path::Path) -> Result<Hash, HashError> {
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    check_len(usize::try_from(len).unwrap_or(usize::MAX))?;
+
+    // SAFETY: the mapping is read-only and dropped before returning; see the
+    // caveat about concurrent modification above.
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    hash_prelowered(&map)
+}
+
+/// Returns true only for a zero-length password, the one input every entry
+/// point rejects with [`HashError::Empty`].
+///
+/// Whitespace is not trimmed: a password of only spaces is a valid, distinct
+/// input and hashes normally, so front-ends should not treat it as blank.
+pub fn is_effectively_empty(password: &[u8]) -> bool {
+    password.is_empty()
+}
+
+fn check_len(len: usize) -> Result<(), HashError> {
+    if len == 0 {
+        return Err(HashError::Empty);
+    }
+    if len > MAX_INPUT_LEN {
+        return Err(HashError::TooLong { len });
+    }
+    Ok(())
+}
+
+fn fold_into(input: &[u8], buffer: &mut [u8; MAX_INPUT_LEN]) -> Result<usize, HashError> {
+    check_len(input.len())?;
+    for (dst, src) in buffer.iter_mut().zip(input) {
+        *dst = src.to_ascii_lowercase();
+    }
+    Ok(input.len())
+}
 
-    Ok(hex_string)
+fn hash_ascii(input: &[u8]) -> Result<Hash, HashError> {
+    let mut buffer = [0u8; MAX_INPUT_LEN];
+    let len = fold_into(input, &mut buffer)?;
+    Ok(digest(&buffer[..len]))
 }
 
-fn calculate_hash(data: &str) -> Result<Vec<u8>, ErrorKind> {
+fn calculate_hash(data: &str) -> Result<Hash, HashError> {
     let lower_case_data = data.to_lowercase();
-    let utf8_bytes = lower_case_data.as_bytes();
+    hash_lowered(lower_case_data.as_bytes())
+}
 
-    if utf8_bytes.len() > 1024 || utf8_bytes.len() == 0 {
-        return Err(ErrorKind::InvalidData);
+fn lowercase_into(data: &str, out: &mut String) {
+    out.clear();
+    if data.is_ascii() {
+        out.push_str(data);
+        out.make_ascii_lowercase();
+    } else {
+        out.push_str(&data.to_lowercase());
     }
+}
+
+fn hash_lowered(utf8_bytes: &[u8]) -> Result<Hash, HashError> {
+    check_len(utf8_bytes.len())?;
+    Ok(digest(utf8_bytes))
+}
 
-    safe_hash(utf8_bytes).map_err(|_| ErrorKind::InvalidData)
+fn digest(input: &[u8]) -> Hash {
+    Hash::from(safe_hash(input))
 }
 
-fn safe_hash(input: &[u8]) -> Result<Vec<u8>, Error> {
-    let mut cursor = Cursor::new(vec![0; 1024]);
+fn safe_hash(input: &[u8]) -> [u8; 20] {
+    let block = load_block(input);
 
-    cursor.write(&input)?;
+    let mut state = IV;
+    process_block(&mut state, &block);
+    state_bytes(state)
+}
 
-    let mut expr_ldata_i: u32;
-    let mut expr_ldata_i_2: u32;
-    let mut expr_ldata_i_8: u32;
-    let mut expr_ldata_i_13: u32;
-    for i in 0..64 {
-        cursor.seek(SeekFrom::Start(i * 4))?;
-        expr_ldata_i = cursor.read_u32::<LittleEndian>()?;
-        cursor.seek(SeekFrom::Current(1 * 4))?;
-        expr_ldata_i_2 = cursor.read_u32::<LittleEndian>()?;
-        cursor.seek(SeekFrom::Current(5 * 4))?;
-        expr_ldata_i_8 = cursor.read_u32::<LittleEndian>()?;
-        cursor.seek(SeekFrom::Current(4 * 4))?;
-        expr_ldata_i_13 = cursor.read_u32::<LittleEndian>()?;
-        let shift_val = (expr_ldata_i ^ expr_ldata_i_8 ^ expr_ldata_i_2 ^ expr_ldata_i_13) & 0x1f;
-        cursor.seek(SeekFrom::Current(2 * 4))?;
-        cursor.write_u32::<LittleEndian>(rol(1, shift_val))?;
+// Serializes the chaining value as the big-endian digest bytes.
+fn state_bytes(state: [u32; 5]) -> [u8; 20] {
+    let mut result = [0u8; 20];
+    for (chunk, word) in result.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
     }
+    result
+}
 
-    let mut a: u32 = 0x67452301;
-    let mut b: u32 = 0xefcdab89;
-    let mut c: u32 = 0x98badcfe;
-    let mut d: u32 = 0x10325476;
-    let mut e: u32 = 0xc3d2e1f0;
-    let mut g: u32 = 0;
+fn load_block(input: &[u8]) -> [u32; 256] {
+    if input.len() <= 16 {
+        return load_short_block(input);
+    }
+    load_block_general(input)
+}
+
+// Most passwords fit in the first four words, which can be filled without
+// staging the whole 1024 byte buffer. The block itself is still zeroed in
+// full, and the 16 and 17 byte benches, one on each side of the cutoff, show
+// no measurable difference from `load_block_general`.
+fn load_short_block(input: &[u8]) -> [u32; 256] {
+    let mut bytes = [0u8; 16];
+    bytes[..input.len()].copy_from_slice(input);
+
+    let mut block = [0u32; 256];
+    block[0] = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    block[1] = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    block[2] = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    block[3] = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+    block
+}
+
+fn load_block_general(input: &[u8]) -> [u32; 256] {
+    let mut bytes = [0u8; MAX_INPUT_LEN];
+    bytes[..input.len()].copy_from_slice(input);
+
+    let mut block = [0u32; 256];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    block
+}
+
+// Offsets from the step index of the words each expansion step reads, and of
+// the word it writes.
+const EXPANSION_TAPS: [usize; 4] = [0, 2, 8, 13];
+const EXPANSION_OUT: usize = 16;
+
+// The expansion writes words EXPANSION_OUT..EXPANSION_OUT + EXPANSION_ROUNDS,
+// i.e. 16..80, and reads as far as word 63 + 13. The four compression loops
+// of COMPRESSION_ROUNDS each consume words 0..80, so these two counts and the
+// offsets above have to change together; `test_expansion_indices_in_bounds`
+// checks the relationship.
+const EXPANSION_ROUNDS: usize = 64;
+const COMPRESSION_ROUNDS: usize = 20;
+
+fn expand(w: &mut [u32; 256]) {
+    for i in 0..EXPANSION_ROUNDS {
+        let shift_val = EXPANSION_TAPS
+            .iter()
+            .fold(0, |acc, tap| acc ^ word_at(w, i + tap))
+            & 0x1f;
+        set_word_at(w, i + EXPANSION_OUT, rol(1, shift_val));
+    }
+}
+
+// All block indexing in the expansion, scalar and SIMD, goes through these
+// two, so a changed offset trips the debug assertion rather than reading a
+// neighbouring word.
+fn word_at<T: Copy, const N: usize>(w: &[T; N], idx: usize) -> T {
+    debug_assert!(idx < w.len(), "block word index {} out of range", idx);
+    w[idx]
+}
 
-    cursor.seek(SeekFrom::Start(0))?;
+fn set_word_at<T, const N: usize>(w: &mut [T; N], idx: usize, value: T) {
+    debug_assert!(idx < w.len(), "block word index {} out of range", idx);
+    w[idx] = value;
+}
 
-    for _ in 0..20 {
-        let temp = cursor.read_u32::<LittleEndian>()?;
+// Must stay private: see "Chaining value" in the crate docs.
+fn process_block(state: &mut [u32; 5], block: &[u32; 256]) {
+    let mut w = *block;
+    expand(&mut w);
+    compress_into(state, &w);
+}
 
+// Runs the rounds over an already expanded block and adds the result onto
+// `state`. Every entry point finishes through here and `state_bytes`.
+fn compress_into(state: &mut [u32; 5], w: &[u32; 256]) {
+    let raw = compress(*state, w);
+    for (word, r) in state.iter_mut().zip(raw) {
+        *word = word.wrapping_add(r);
+    }
+}
+
+// Runs the 80 rounds over an expanded block, returning the working variables
+// before they are added back onto the chaining value.
+fn compress(state: [u32; 5], w: &[u32; 256]) -> [u32; 5] {
+    compress_traced(state, w, |_, _| {})
+}
+
+// `observe` is called with the round number and working variables after each
+// of the 80 rounds.
+fn compress_traced<F: FnMut(usize, [u32; 5])>(
+    state: [u32; 5],
+    w: &[u32; 256],
+    mut observe: F,
+) -> [u32; 5] {
+    let [mut a, mut b, mut c, mut d, mut e] = state;
+    let mut g: u32 = 0;
+
+    for (i, &temp) in w[..COMPRESSION_ROUNDS].iter().enumerate() {
         g = temp
             .wrapping_add(rol(a, 5))
             .wrapping_add(e)
@@ -81,10 +619,13 @@ fn safe_hash(input: &[u8]) -> Result<Vec<u8>, Error> {
         c = rol(b, 30);
         b = a;
         a = g;
+        observe(i, [a, b, c, d, e]);
     }
 
-    for _ in 0..20 {
-        let temp = cursor.read_u32::<LittleEndian>()?;
+    for (i, &temp) in w[COMPRESSION_ROUNDS..2 * COMPRESSION_ROUNDS]
+        .iter()
+        .enumerate()
+    {
         g = (d ^ c ^ b)
             .wrapping_add(e)
             .wrapping_add(rol(g, 5))
@@ -96,10 +637,13 @@ fn safe_hash(input: &[u8]) -> Result<Vec<u8>, Error> {
         c = rol(b, 30);
         b = a;
         a = g;
+        observe(COMPRESSION_ROUNDS + i, [a, b, c, d, e]);
     }
 
-    for _ in 0..20 {
-        let temp = cursor.read_u32::<LittleEndian>()?;
+    for (i, &temp) in w[2 * COMPRESSION_ROUNDS..3 * COMPRESSION_ROUNDS]
+        .iter()
+        .enumerate()
+    {
         g = temp
             .wrapping_add(rol(g, 5))
             .wrapping_add(e)
@@ -111,10 +655,13 @@ fn safe_hash(input: &[u8]) -> Result<Vec<u8>, Error> {
         c = rol(b, 30);
         b = a;
         a = g;
+        observe(2 * COMPRESSION_ROUNDS + i, [a, b, c, d, e]);
     }
 
-    for _ in 0..20 {
-        let temp = cursor.read_u32::<LittleEndian>()?;
+    for (i, &temp) in w[3 * COMPRESSION_ROUNDS..4 * COMPRESSION_ROUNDS]
+        .iter()
+        .enumerate()
+    {
         g = (d ^ c ^ b)
             .wrapping_add(e)
             .wrapping_add(rol(g, 5))
@@ -126,21 +673,10 @@ fn safe_hash(input: &[u8]) -> Result<Vec<u8>, Error> {
         c = rol(b, 30);
         b = a;
         a = g;
+        observe(3 * COMPRESSION_ROUNDS + i, [a, b, c, d, e]);
     }
 
-    let mut result = Cursor::new(vec![0; 20]);
-    a = a.wrapping_add(0x67452301);
-    b = b.wrapping_add(0xefcdab89);
-    c = c.wrapping_add(0x98badcfe);
-    d = d.wrapping_add(0x10325476);
-    e = e.wrapping_add(0xc3d2e1f0);
-    result.write_u32::<BigEndian>(a)?;
-    result.write_u32::<BigEndian>(b)?;
-    result.write_u32::<BigEndian>(c)?;
-    result.write_u32::<BigEndian>(d)?;
-    result.write_u32::<BigEndian>(e)?;
-
-    Ok(result.into_inner())
+    [a, b, c, d, e]
 }
 
 fn rol(val: u32, shift: u32) -> u32 {
@@ -150,14 +686,22 @@ fn rol(val: u32, shift: u32) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use crate::{get_hash_bytes, get_hash_string};
-    use std::io::ErrorKind;
+    use crate::{
+        double_hash_hex, expand, get_hash_bytes, get_hash_bytes_raw, get_hash_colon_upper,
+        get_hash_grouped, get_hash_string, hamming_distance, hash_all_hex, hash_bufread,
+        hash_bytes_iter, hash_checksum, hash_in_place, hash_prefixes, hash_prelowered,
+        hash_username, hash_utf16le, hash_with_scratch, is_effectively_empty, load_block,
+        load_block_general, load_short_block, passwords_collide, process_block, rol, verify,
+        verify_hex_ci, verify_str, word_at, BrokenSha1, Hash, HashError, HashErrorKind,
+        HashOptions, HashWords, WeakPasswordSet, COMPRESSION_ROUNDS, EXPANSION_OUT,
+        EXPANSION_ROUNDS, EXPANSION_TAPS, IV,
+    };
 
     #[test]
     fn test_empty_password_with_bytes() {
         let result = get_hash_bytes(vec![]);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), ErrorKind::InvalidData);
+        assert_eq!(result.unwrap_err(), HashError::Empty);
     }
 
     #[test]
@@ -182,8 +726,792 @@ mod tests {
     #[test]
     fn test_valid_password_with_string() {
         let password = "12345";
-        let result = get_hash_string(&password);
+        let result = get_hash_string(password);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
     }
+
+    #[test]
+    fn test_iter_matches_string() {
+        let result = hash_bytes_iter("12345".bytes());
+        assert_eq!(
+            result.unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+    }
+
+    #[test]
+    fn test_iter_folds_ascii_case() {
+        let upper = hash_bytes_iter("PassWord".bytes()).unwrap();
+        let lower = hash_bytes_iter("password".bytes()).unwrap();
+        assert_eq!(upper, lower);
+    }
+
+    #[test]
+    fn test_iter_empty() {
+        assert_eq!(hash_bytes_iter(std::iter::empty()), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_iter_length_cap() {
+        assert!(hash_bytes_iter(std::iter::repeat_n(b'a', 1024)).is_ok());
+        assert_eq!(
+            hash_bytes_iter(std::iter::repeat(b'a')),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    // Hands out at most `chunk` bytes per `fill_buf`, like a socket would.
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl std::io::Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = std::io::BufRead::fill_buf(self)?.read(buf)?;
+            std::io::BufRead::consume(self, n);
+            Ok(n)
+        }
+    }
+
+    impl std::io::BufRead for Chunked<'_> {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            Ok(&self.data[..self.chunk.min(self.data.len())])
+        }
+
+        fn consume(&mut self, n: usize) {
+            self.data = &self.data[n..];
+        }
+    }
+
+    #[test]
+    fn test_hash_bufread() {
+        let mut r = Chunked {
+            data: b"12345",
+            chunk: 2,
+        };
+        assert_eq!(
+            hash_bufread(&mut r).unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+
+        let mut empty = Chunked {
+            data: b"",
+            chunk: 8,
+        };
+        assert_eq!(hash_bufread(&mut empty), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_hash_bufread_exactly_cap() {
+        let data = [b'A'; 1024];
+        let mut r = Chunked {
+            data: &data,
+            chunk: 100,
+        };
+        assert_eq!(
+            hash_bufread(&mut r),
+            hash_bytes_iter([b'a'; 1024].into_iter())
+        );
+    }
+
+    #[test]
+    fn test_hash_bufread_over_cap_leaves_rest() {
+        let mut data = vec![b'a'; 1024];
+        data.extend_from_slice(b"xyz");
+        let mut r = Chunked {
+            data: &data,
+            chunk: 1000,
+        };
+        assert_eq!(hash_bufread(&mut r), Err(HashError::TooLong { len: 1025 }));
+        assert_eq!(r.data, b"xyz");
+    }
+
+    #[test]
+    fn test_hash_bufread_io_error() {
+        struct Failing;
+
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+        }
+
+        let mut r = std::io::BufReader::new(Failing);
+        assert_eq!(
+            hash_bufread(&mut r),
+            Err(HashError::Io(std::io::ErrorKind::BrokenPipe))
+        );
+    }
+
+    #[test]
+    fn test_process_block_single_block_state() {
+        let mut state = IV;
+        process_block(&mut state, &load_block(b"12345"));
+        assert_eq!(
+            state,
+            [0x460e0af6, 0xc1828a93, 0xfe887cbe, 0x103d6ca6, 0xab97a0e4]
+        );
+    }
+
+    #[cfg(feature = "instrumented")]
+    #[test]
+    fn test_hash_timed() {
+        let (hash, _elapsed) = crate::hash_timed(b"12345").unwrap();
+        assert_eq!(hash.to_string(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+        assert_eq!(crate::hash_timed(b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_get_hash_string_matches_display() {
+        for password in ["12345", "Admin", "pass", "Café", "密码", &"x".repeat(1024)] {
+            let bytes = get_hash_bytes(password.as_bytes().to_vec()).unwrap();
+            let bytes: [u8; 20] = bytes.try_into().unwrap();
+            assert_eq!(
+                Hash::from(bytes).to_string(),
+                get_hash_string(password).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_all_hex() {
+        let long = "a".repeat(1025);
+        let results = hash_all_hex(&["12345", "", "ÄBC", "abc", &long]);
+
+        assert_eq!(
+            results[0].as_deref(),
+            Ok("460e0af6c1828a93fe887cbe103d6ca6ab97a0e4")
+        );
+        assert_eq!(results[1], Err(HashError::Empty));
+        assert_eq!(
+            results[2].as_ref().ok(),
+            get_hash_string("äbc").as_ref().ok()
+        );
+        assert_eq!(
+            results[3].as_ref().ok(),
+            get_hash_string("ABC").as_ref().ok()
+        );
+        assert_eq!(results[4], Err(HashError::TooLong { len: 1025 }));
+    }
+
+    #[test]
+    fn test_rol_shift_edges() {
+        assert_eq!(rol(1, 0), 1);
+        assert_eq!(rol(1, 31), 0x8000_0000);
+    }
+
+    #[test]
+    fn test_expansion_zero_shift() {
+        // words 2, 4, 10 and 15 of "12345" are all zero, so word 18 gets rol(1, 0)
+        let mut w = load_block(b"12345");
+        assert_eq!((w[2] ^ w[4] ^ w[10] ^ w[15]) & 0x1f, 0);
+        expand(&mut w);
+        assert_eq!(w[18], 1);
+    }
+
+    #[test]
+    fn test_expansion_max_shift() {
+        // '?' is 0x3f, so word 0 masks to a shift of 31 for word 16
+        let mut w = load_block(b"?");
+        assert_eq!((w[0] ^ w[2] ^ w[8] ^ w[13]) & 0x1f, 31);
+        expand(&mut w);
+        assert_eq!(w[16], 0x8000_0000);
+    }
+
+    #[test]
+    fn test_double_hash_hex() {
+        let hash = double_hash_hex(b"12345").unwrap();
+        assert_eq!(hash.to_string(), "cf605a3dfae8b1ad79b8d75fd50f9dcf187a6698");
+        assert_eq!(double_hash_hex(b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_zero_length_is_empty() {
+        assert!(is_effectively_empty(b""));
+        assert_eq!(hash_bytes_iter(std::iter::empty()), Err(HashError::Empty));
+        assert_eq!(get_hash_string(""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_spaces_only_is_hashed() {
+        assert!(!is_effectively_empty(b"   "));
+        let spaces = get_hash_string("   ").unwrap();
+        assert_ne!(spaces, get_hash_string(" ").unwrap());
+        assert_eq!(hash_bytes_iter("   ".bytes()).unwrap().to_string(), spaces);
+    }
+
+    #[test]
+    fn test_get_hash_grouped() {
+        assert_eq!(
+            get_hash_grouped("12345", 8, '-').unwrap(),
+            "460e0af6-c1828a93-fe887cbe-103d6ca6-ab97a0e4"
+        );
+        assert_eq!(
+            get_hash_grouped("12345", 6, ' ').unwrap(),
+            "460e0a f6c182 8a93fe 887cbe 103d6c a6ab97 a0e4"
+        );
+        assert_eq!(
+            get_hash_grouped("12345", 40, '-').unwrap(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+        assert_eq!(
+            get_hash_grouped("12345", 0, '-').unwrap(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+        assert_eq!(get_hash_grouped("", 8, '-'), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_get_hash_colon_upper() {
+        assert_eq!(
+            get_hash_colon_upper("12345").unwrap(),
+            "46:0E:0A:F6:C1:82:8A:93:FE:88:7C:BE:10:3D:6C:A6:AB:97:A0:E4"
+        );
+        assert_eq!(get_hash_colon_upper(""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_verify() {
+        let stored = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4".parse().unwrap();
+        assert_eq!(verify("12345", &stored), Ok(true));
+        assert_eq!(verify("54321", &stored), Ok(false));
+        assert_eq!(verify("", &stored), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_hash_checksum() {
+        assert_eq!(hash_checksum(b"12345"), Ok(0x460e0af6));
+        assert_eq!(hash_checksum(b"PvPGN"), hash_checksum(b"pvpgn"));
+        assert_eq!(hash_checksum(b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_passwords_collide() {
+        assert_eq!(passwords_collide(b"12345", b"54321"), Ok(false));
+        assert_eq!(passwords_collide(b"12345", b"12345"), Ok(false));
+
+        assert_eq!(passwords_collide(b"PvPGN", b"pvpgn"), Ok(true));
+        assert_eq!(passwords_collide(b"a", b"a\0"), Ok(true));
+
+        assert_eq!(passwords_collide(b"a", b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(b"12345", b"12345"), Ok(0));
+        assert_eq!(hamming_distance(b"Admin", b"admin"), Ok(0));
+
+        let distance = hamming_distance(b"12345", b"12346").unwrap();
+        assert_eq!(distance, 76);
+        assert!((40..=120).contains(&distance));
+        assert_eq!(
+            hamming_distance(b"12345", b"54321"),
+            hamming_distance(b"54321", b"12345")
+        );
+
+        assert_eq!(hamming_distance(b"", b"a"), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_hash_prefixes() {
+        let full = get_hash_bytes_raw(b"12345").unwrap();
+        assert_eq!(
+            hash_prefixes(b"12345", &[4, 8, 20]).unwrap(),
+            [&full[..4], &full[..8], &full[..]]
+        );
+        assert_eq!(hash_prefixes(b"12345", &[0]).unwrap(), [Vec::<u8>::new()]);
+        assert!(hash_prefixes(b"12345", &[]).unwrap().is_empty());
+
+        assert_eq!(
+            hash_prefixes(b"12345", &[4, 21]),
+            Err(HashError::PrefixTooLong { len: 21 })
+        );
+        assert_eq!(hash_prefixes(b"", &[4]), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_hash_with_scratch() {
+        let mut scratch = [0xdead_beef; 256];
+        for input in [&b"12345"[..], b"PvPGN", b"a", &[b'Q'; 17], &[b'x'; 1024]] {
+            assert_eq!(
+                hash_with_scratch(input, &mut scratch),
+                hash_bytes_iter(input.iter().copied())
+            );
+        }
+
+        // a long input's words must not leak into a following short one
+        hash_with_scratch(&[b'z'; 1024], &mut scratch).unwrap();
+        assert_eq!(
+            hash_with_scratch(b"12345", &mut scratch)
+                .unwrap()
+                .to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+
+        assert_eq!(hash_with_scratch(b"", &mut scratch), Err(HashError::Empty));
+        assert_eq!(
+            hash_with_scratch(&[b'a'; 1025], &mut scratch),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_public_types_are_send_sync() {
+        assert_send_sync::<Hash>();
+        assert_send_sync::<HashWords>();
+        assert_send_sync::<HashError>();
+        assert_send_sync::<HashErrorKind>();
+        assert_send_sync::<HashOptions>();
+        assert_send_sync::<BrokenSha1>();
+        assert_send_sync::<WeakPasswordSet>();
+        #[cfg(feature = "ratelimit")]
+        assert_send_sync::<crate::ratelimit::RateLimitedHasher>();
+    }
+
+    #[cfg(feature = "secrecy")]
+    #[test]
+    fn test_get_hash_secret() {
+        use secrecy::ExposeSecret;
+
+        let secret = crate::get_hash_secret("12345").unwrap();
+        let hex = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+        assert_eq!(secret.expose_secret().to_string(), hex);
+
+        let debug = format!("{:?}", secret);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("460e0af6"));
+        assert!(!debug.contains("70, 14"));
+
+        assert!(crate::get_hash_secret("").is_err());
+    }
+
+    #[cfg(any(feature = "zeroize", feature = "secrecy"))]
+    #[test]
+    fn test_hash_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut hash = hash_prelowered(b"12345").unwrap();
+        hash.zeroize();
+        assert_eq!(hash, Hash::from([0; 20]));
+    }
+
+    #[test]
+    fn test_hash_username() {
+        let expected = "e9105a5fc971179bd3540a61d3e09aad65bf89d6";
+        assert_eq!(hash_username("Admin").unwrap().to_string(), expected);
+        assert_eq!(hash_username("ADMIN").unwrap().to_string(), expected);
+        assert_eq!(
+            hash_username("Admin").unwrap().to_string(),
+            get_hash_string("Admin").unwrap()
+        );
+        assert_eq!(hash_username(""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_verify_str() {
+        assert_eq!(
+            verify_str("12345", "460E0AF6C1828A93FE887CBE103D6CA6AB97A0E4"),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_str("12345", "460e0af6"),
+            Err(HashError::InvalidHexLength { len: 8 })
+        );
+    }
+
+    #[test]
+    fn test_get_hash_bytes_raw() {
+        assert_eq!(
+            get_hash_bytes_raw(b"12345"),
+            get_hash_bytes(b"12345".to_vec())
+        );
+        assert_eq!(
+            get_hash_bytes_raw(b"PvPGN"),
+            get_hash_bytes(b"pvpgn".to_vec())
+        );
+
+        let binary = [0xff, 0xfe, b'A', 0x00, 0x80];
+        assert_eq!(get_hash_bytes(binary.to_vec()), Err(HashError::InvalidUtf8));
+        assert_eq!(
+            get_hash_bytes_raw(&binary).unwrap(),
+            hash_bytes_iter(binary.iter().copied()).unwrap().as_bytes()
+        );
+
+        assert_eq!(get_hash_bytes_raw(b""), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_invalid_utf8_with_bytes() {
+        assert_eq!(
+            get_hash_bytes(vec![0xff, 0xfe]),
+            Err(HashError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn test_hash_in_place() {
+        let mut buf = b"PvPGN".to_vec();
+        assert_eq!(hash_in_place(&mut buf), hash_bytes_iter("PvPGN".bytes()));
+        #[cfg(not(feature = "zeroize"))]
+        assert_eq!(buf, b"pvpgn");
+        #[cfg(feature = "zeroize")]
+        assert!(buf.is_empty());
+
+        let mut buf = b"12345".to_vec();
+        assert_eq!(
+            hash_in_place(&mut buf).unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+
+        assert_eq!(hash_in_place(&mut Vec::new()), Err(HashError::Empty));
+        let mut long = vec![b'A'; 1025];
+        assert_eq!(
+            hash_in_place(&mut long),
+            Err(HashError::TooLong { len: 1025 })
+        );
+        #[cfg(feature = "zeroize")]
+        assert!(long.is_empty());
+    }
+
+    #[test]
+    fn test_hash_prelowered() {
+        let hash = hash_prelowered(b"12345").unwrap();
+        assert_eq!(hash.to_string(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+        assert_eq!(hash_prelowered(b"abc"), hash_bytes_iter("ABC".bytes()));
+        assert_eq!(hash_prelowered(b""), Err(HashError::Empty));
+        assert_eq!(
+            hash_prelowered(&[b'a'; 1025]),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "uppercase")]
+    fn test_hash_prelowered_rejects_uppercase_in_debug() {
+        let _ = hash_prelowered(b"Abc");
+    }
+
+    #[test]
+    fn test_short_block_matches_general() {
+        let mut seed: u32 = 0x2545_f491;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+
+        for _ in 0..1000 {
+            let len = (next() % 16) as usize + 1;
+            let input: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+
+            let short = load_short_block(&input);
+            assert_eq!(short, load_block_general(&input));
+
+            let (mut fast, mut general) = (IV, IV);
+            process_block(&mut fast, &short);
+            process_block(&mut general, &load_block_general(&input));
+            assert_eq!(fast, general);
+        }
+    }
+
+    // xorshift32 from a fixed seed, keeping the low byte of each step. The
+    // expected digest was computed independently by a Python port of the hash,
+    // over the bytes after the ASCII fold every entry point applies:
+    //
+    //     x = 0x2545f491
+    //     for _ in range(1024):
+    //         x ^= (x << 13) & 0xffffffff; x ^= x >> 17; x ^= (x << 5) & 0xffffffff
+    //         out.append(x & 0xff)
+    //     data = bytes(out).lower()  # folds A-Z only
+    //
+    // Hashing `bytes(out)` without the fold gives babf39be... instead.
+    fn full_buffer_input() -> [u8; 1024] {
+        let mut x: u32 = 0x2545f491;
+        let mut out = [0u8; 1024];
+        for b in out.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *b = x as u8;
+        }
+        out
+    }
+
+    #[test]
+    fn test_full_buffer_kat() {
+        let input = full_buffer_input();
+        assert_eq!(
+            &input[..8],
+            [0x3a, 0xab, 0xac, 0x26, 0xaf, 0x23, 0x1a, 0x71]
+        );
+
+        let expected = "51f6aaab995ebb10bd64ecd75ee7ee7c3aba1b0b";
+        let hash = hash_bytes_iter(input.iter().copied()).unwrap();
+        assert_eq!(hash.to_string(), expected);
+        assert_eq!(hash_with_scratch(&input, &mut [0; 256]), Ok(hash));
+
+        // Only the first 64 bytes reach the rounds, so the other 960 exercise
+        // loading and the length check but not the digest.
+        assert_eq!(hash_bytes_iter(input[..64].iter().copied()), Ok(hash));
+    }
+
+    #[test]
+    fn test_multibyte_within_cap() {
+        let odd = format!("{}a", "ñ".repeat(511));
+        assert_eq!(odd.len(), 1023);
+        assert!(get_hash_string(&odd).is_ok());
+
+        let even = "ñ".repeat(512);
+        assert_eq!(even.len(), 1024);
+        assert!(get_hash_string(&even).is_ok());
+        assert_eq!(get_hash_string(&"Ñ".repeat(512)), get_hash_string(&even));
+    }
+
+    #[test]
+    fn test_multibyte_over_cap() {
+        let over = format!("{}ñ", "a".repeat(1023));
+        assert_eq!(over.len(), 1025);
+        assert_eq!(
+            get_hash_string(&over),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    #[test]
+    fn test_cap_checks_lowercased_length() {
+        // 'İ' is two bytes but lowercases to "i\u{307}", which is three
+        let dotted = "İ".repeat(512);
+        assert_eq!(dotted.len(), 1024);
+        assert_eq!(
+            get_hash_string(&dotted),
+            Err(HashError::TooLong { len: 1536 })
+        );
+    }
+
+    #[test]
+    fn test_hash_utf16le() {
+        let units: Vec<u16> = "12345".encode_utf16().collect();
+        assert_eq!(
+            hash_utf16le(&units).unwrap().to_string(),
+            "023a3a87c91e212117f0db2374a19f3a95d3c237"
+        );
+
+        let upper: Vec<u16> = "PaSS".encode_utf16().collect();
+        assert_eq!(
+            hash_utf16le(&upper).unwrap().to_string(),
+            "ffbed12298dc2020b18b63e42a894a80ec366a64"
+        );
+    }
+
+    #[test]
+    fn test_hash_utf16le_folds_units_not_bytes() {
+        // the high byte 0x41 of U+4100 must not be folded to 0x61
+        assert_ne!(hash_utf16le(&[0x4100]), hash_utf16le(&[0x6100]));
+        assert_eq!(hash_utf16le(&[0x0041]), hash_utf16le(&[0x0061]));
+    }
+
+    #[test]
+    fn test_hash_utf16le_length() {
+        assert_eq!(hash_utf16le(&[]), Err(HashError::Empty));
+        assert!(hash_utf16le(&[0x61; 512]).is_ok());
+        assert_eq!(
+            hash_utf16le(&[0x61; 513]),
+            Err(HashError::TooLong { len: 1026 })
+        );
+    }
+
+    #[test]
+    fn test_verify_hex_ci() {
+        let lower = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+        let upper = lower.to_ascii_uppercase();
+        let mixed = "460E0aF6c1828A93fe887CBE103d6ca6AB97a0e4";
+
+        assert_eq!(verify_hex_ci("12345", lower), Ok(true));
+        assert_eq!(verify_hex_ci("12345", &upper), Ok(true));
+        assert_eq!(verify_hex_ci("12345", mixed), Ok(true));
+        assert_eq!(verify_hex_ci("123456", &upper), Ok(false));
+        assert_eq!(
+            verify_hex_ci("12345", "zz0e0af6c1828a93fe887cbe103d6ca6ab97a0e4"),
+            Err(HashError::InvalidHexDigit { index: 0 })
+        );
+    }
+
+    #[cfg(feature = "encodings")]
+    #[test]
+    fn test_hash_cp1252() {
+        // "Café" in Windows-1252
+        let cafe = crate::hash_cp1252(b"Caf\xe9").unwrap();
+        assert_eq!(cafe.to_string(), "68e53d34fb949c6becfcbfcced6c8e8d1f6dc149");
+        assert_eq!(crate::hash_cp1252(b"CAF\xe9"), Ok(cafe));
+
+        // only ASCII is folded, and the bytes are not the UTF-8 encoding
+        assert_ne!(crate::hash_cp1252(b"CAF\xc9"), Ok(cafe));
+        assert_ne!(get_hash_string("Café").unwrap(), cafe.to_string());
+
+        assert_eq!(crate::hash_cp1252(b""), Err(HashError::Empty));
+    }
+
+    #[cfg(all(feature = "mmap", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "uppercase")]
+    fn test_hash_mmap_rejects_uppercase_in_debug() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"Abc").unwrap();
+        file.flush().unwrap();
+        let _ = crate::hash_mmap(file.path());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_hash_mmap() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"12345").unwrap();
+        file.flush().unwrap();
+        assert_eq!(
+            crate::hash_mmap(file.path()).unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+
+        let empty = tempfile::NamedTempFile::new().unwrap();
+        assert_eq!(crate::hash_mmap(empty.path()), Err(HashError::Empty));
+
+        let mut long = tempfile::NamedTempFile::new().unwrap();
+        long.write_all(&[b'a'; 1025]).unwrap();
+        long.flush().unwrap();
+        assert_eq!(
+            crate::hash_mmap(long.path()),
+            Err(HashError::TooLong { len: 1025 })
+        );
+
+        let missing = empty.path().with_extension("missing");
+        assert_eq!(
+            crate::hash_mmap(&missing),
+            Err(HashError::Io(std::io::ErrorKind::NotFound))
+        );
+    }
+
+    #[test]
+    fn test_subtracted_constants_are_sha1_constants() {
+        // The C source adds negative 32-bit constants; as unsigned values
+        // they are SHA-1's third and fourth round constants.
+        for x in [0u32, 1, 0x7fff_ffff, 0x8000_0000, 0xdead_beef, u32::MAX] {
+            assert_eq!(x.wrapping_sub(0x70E44324), x.wrapping_add(0x8F1BBCDC));
+            assert_eq!(x.wrapping_sub(0x359d3e2a), x.wrapping_add(0xCA62C1D6));
+        }
+        assert_eq!(-0x70E44324i32 as u32, 0x8F1BBCDC);
+        assert_eq!(-0x359d3e2ai32 as u32, 0xCA62C1D6);
+    }
+
+    #[test]
+    fn test_expansion_indices_in_bounds() {
+        let max_read = (0..EXPANSION_ROUNDS)
+            .flat_map(|i| EXPANSION_TAPS.iter().map(move |tap| i + tap))
+            .max()
+            .unwrap();
+        let max_write = (0..EXPANSION_ROUNDS)
+            .map(|i| i + EXPANSION_OUT)
+            .max()
+            .unwrap();
+
+        assert_eq!(max_read, 63 + 13);
+        assert_eq!(max_write, 63 + 16);
+        assert!(max_write < 256);
+
+        // every step reads only words written by the load or an earlier step:
+        // step i reads i + tap and writes i + EXPANSION_OUT
+        assert!(EXPANSION_TAPS.iter().all(|&tap| tap < EXPANSION_OUT));
+
+        // the rounds read words 0..80, which is exactly what the expansion fills
+        assert_eq!(max_write + 1, 4 * COMPRESSION_ROUNDS);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_word_at_asserts_in_debug() {
+        let w = [0u32; 256];
+        word_at(&w, 256);
+    }
+
+    mod fold_properties {
+        use crate::{
+            fold_into, get_hash_string, hash_bytes_iter, hash_in_place, hash_prelowered,
+            BrokenSha1, HashErrorKind, MAX_INPUT_LEN,
+        };
+        use proptest::prelude::*;
+
+        // fold_into rejects empty input, which some properties still cover
+        fn fold(x: &[u8]) -> Vec<u8> {
+            if x.is_empty() {
+                return Vec::new();
+            }
+            let mut buffer = [0u8; MAX_INPUT_LEN];
+            let len = fold_into(x, &mut buffer).unwrap();
+            buffer[..len].to_vec()
+        }
+
+        proptest! {
+            #[test]
+            fn test_fold_is_idempotent(x in prop::collection::vec(any::<u8>(), 1..=1024)) {
+                let folded = fold(&x);
+                prop_assert_eq!(&fold(&folded), &folded);
+
+                let expected = hash_bytes_iter(x.iter().copied());
+                let mut buf = x.clone();
+                prop_assert_eq!(&hash_in_place(&mut buf), &expected);
+                // zeroize wipes the buffer, so only check what it holds without it
+                #[cfg(not(feature = "zeroize"))]
+                prop_assert_eq!(&buf, &folded);
+                prop_assert_eq!(&hash_in_place(&mut folded.clone()), &expected);
+            }
+
+            #[test]
+            fn test_over_length_is_rejected(x in prop::collection::vec(any::<u8>(), 1025..=1100)) {
+                let too_long = Some(HashErrorKind::TooLong);
+                prop_assert_eq!(hash_bytes_iter(x.iter().copied()).err().map(|e| e.kind()), too_long);
+                prop_assert_eq!(hash_prelowered(&x.to_ascii_lowercase()).err().map(|e| e.kind()), too_long);
+                prop_assert_eq!(hash_in_place(&mut x.clone()).err().map(|e| e.kind()), too_long);
+                prop_assert_eq!(fold_into(&x, &mut [0; MAX_INPUT_LEN]).err().map(|e| e.kind()), too_long);
+
+                let mut hasher = BrokenSha1::new();
+                prop_assert_eq!(hasher.update(&x).err().map(|e| e.kind()), too_long);
+            }
+
+            #[test]
+            fn test_hash_ignores_prior_fold(x in prop::collection::vec(any::<u8>(), 0..=1024)) {
+                let raw = hash_bytes_iter(x.iter().copied());
+                prop_assert_eq!(&raw, &hash_bytes_iter(fold(&x).into_iter()));
+                prop_assert_eq!(&raw, &hash_prelowered(&fold(&x)));
+            }
+
+            #[test]
+            fn test_streaming_folds_like_one_shot(
+                x in prop::collection::vec(any::<u8>(), 1..=1024),
+                split in any::<prop::sample::Index>(),
+            ) {
+                let (head, tail) = x.split_at(split.index(x.len() + 1));
+                let mut hasher = BrokenSha1::new();
+                hasher.update(head).unwrap();
+                hasher.update(tail).unwrap();
+                prop_assert_eq!(hasher.finalize(), hash_bytes_iter(x.iter().copied()));
+            }
+
+            #[test]
+            fn test_unicode_fold_agrees_on_ascii(s in "[ -~]{1,100}") {
+                let via_str = get_hash_string(&s).unwrap();
+                let via_bytes = hash_bytes_iter(s.bytes()).unwrap().to_string();
+                prop_assert_eq!(via_str, via_bytes);
+            }
+        }
+    }
 }
@@ -0,0 +1,238 @@
+use crate::{check_len, digest, fold_into, Hash, HashError, MAX_INPUT_LEN};
+
+/// How the five result words are laid out in the 20 digest bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Most significant byte first, as PvPGN stores `passhash1`.
+    Big,
+    /// Least significant byte first, as some tools dump the raw words.
+    Little,
+}
+
+/// Policy for [`hash_with`], built from [`HashOptions::pvpgn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashOptions {
+    min_len: usize,
+    output_order: ByteOrder,
+    c_compatible: bool,
+    allow_empty: bool,
+}
+
+impl HashOptions {
+    /// Stock PvPGN behaviour: ASCII case folding and 1 to 1024 bytes.
+    pub fn pvpgn() -> Self {
+        HashOptions {
+            min_len: 1,
+            output_order: ByteOrder::Big,
+            c_compatible: false,
+            allow_empty: false,
+        }
+    }
+
+    /// Rejects passwords shorter than `n` bytes with `TooShort`. Empty input
+    /// is always reported as `Empty`.
+    pub fn min_len(mut self, n: usize) -> Self {
+        self.min_len = n;
+        self
+    }
+
+    /// Serializes each result word in `order`. The words themselves are the
+    /// same either way; `Big` is the stock layout.
+    pub fn output_order(mut self, order: ByteOrder) -> Self {
+        self.output_order = order;
+        self
+    }
+
+    /// Hashes the password as a C string: it ends at its first NUL byte, as
+    /// `strlen` would end it, and the length checks apply to the bytes before
+    /// that NUL. Only `A`-`Z` are folded, and other bytes, including
+    /// non-ASCII ones, are hashed as they are.
+    ///
+    /// This is meant to follow pvpgn-server, whose `bnet_hash` is handed a
+    /// lowercased password and its `strlen`. It has not been checked against
+    /// a particular pvpgn-server release or commit, and the vectors in the
+    /// tests come from an independent port of the hash, not from a running
+    /// server, so treat agreement with a given server build as unverified.
+    ///
+    /// ASCII folding and raw-byte lengths are already the default here, so in
+    /// practice the switch adds the NUL truncation.
+    pub fn c_compatible(mut self, enabled: bool) -> Self {
+        self.c_compatible = enabled;
+        self
+    }
+
+    /// Hashes zero-length input instead of rejecting it. The input then adds
+    /// nothing to the all-zero buffer, so every empty password gets the same
+    /// digest, `4d3aa0ee94261d5a584a6f576b8d99601546c680`.
+    ///
+    /// Stock PvPGN rejects empty passwords, so this is off by default. When
+    /// enabled, empty input is accepted regardless of
+    /// [`min_len`](Self::min_len).
+    pub fn allow_empty(mut self, enabled: bool) -> Self {
+        self.allow_empty = enabled;
+        self
+    }
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        HashOptions::pvpgn()
+    }
+}
+
+/// Hashes `password` like [`hash_bytes_iter`](crate::hash_bytes_iter) after
+/// applying the checks in `options`.
+pub fn hash_with(password: &[u8], options: HashOptions) -> Result<Hash, HashError> {
+    let password = match password.iter().position(|&b| b == 0) {
+        Some(nul) if options.c_compatible => &password[..nul],
+        _ => password,
+    };
+
+    let hash = if password.is_empty() && options.allow_empty {
+        digest(&[])
+    } else {
+        check_len(password.len())?;
+        if password.len() < options.min_len {
+            return Err(HashError::TooShort {
+                len: password.len(),
+                min: options.min_len,
+            });
+        }
+
+        let mut buffer = [0u8; MAX_INPUT_LEN];
+        let len = fold_into(password, &mut buffer)?;
+        digest(&buffer[..len])
+    };
+
+    Ok(match options.output_order {
+        ByteOrder::Big => hash,
+        ByteOrder::Little => {
+            let mut bytes = hash.into_bytes();
+            for word in bytes.chunks_exact_mut(4) {
+                word.reverse();
+            }
+            Hash::from(bytes)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash_bytes_iter, hash_with, ByteOrder, HashError, HashOptions};
+
+    #[test]
+    fn test_default_matches_stock() {
+        let hash = hash_with(b"12345", HashOptions::pvpgn()).unwrap();
+        assert_eq!(hash.to_string(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+        assert_eq!(HashOptions::default(), HashOptions::pvpgn());
+    }
+
+    #[test]
+    fn test_default_rejects_only_empty() {
+        let options = HashOptions::pvpgn();
+        assert_eq!(hash_with(b"", options), Err(HashError::Empty));
+        assert_eq!(hash_with(b"a", options), hash_bytes_iter("a".bytes()));
+    }
+
+    #[test]
+    fn test_min_len() {
+        let options = HashOptions::pvpgn().min_len(6);
+        assert_eq!(
+            hash_with(b"12345", options),
+            Err(HashError::TooShort { len: 5, min: 6 })
+        );
+        assert!(hash_with(b"123456", options).is_ok());
+        assert!(hash_with(b"1234567", options).is_ok());
+        assert_eq!(hash_with(b"", options), Err(HashError::Empty));
+        assert_eq!(
+            hash_with(&[b'a'; 1025], options),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    #[test]
+    fn test_output_order() {
+        let big = HashOptions::pvpgn().output_order(ByteOrder::Big);
+        assert_eq!(big, HashOptions::pvpgn());
+        assert_eq!(
+            hash_with(b"12345", big).unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+
+        let little = HashOptions::pvpgn().output_order(ByteOrder::Little);
+        assert_eq!(
+            hash_with(b"12345", little).unwrap().to_string(),
+            "f60a0e46938a82c1be7c88fea66c3d10e4a097ab"
+        );
+        assert_eq!(hash_with(b"", little), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_c_compatible_stops_at_nul() {
+        let c = HashOptions::pvpgn().c_compatible(true);
+        let abc = "7bf57a7acc1be999f975dce65522fe81ab78c8d6";
+
+        assert_eq!(hash_with(b"abc\0def", c).unwrap().to_string(), abc);
+        assert_eq!(hash_with(b"ABC", c).unwrap().to_string(), abc);
+        assert_eq!(
+            hash_with(b"abc\0def", HashOptions::pvpgn())
+                .unwrap()
+                .to_string(),
+            "9dd5419e841e312c99f281824fd84235d758568f"
+        );
+
+        // non-ASCII bytes are not folded in either mode, only truncated
+        let cafe = "68e53d34fb949c6becfcbfcced6c8e8d1f6dc149";
+        assert_eq!(hash_with(b"CAF\xe9\0x", c).unwrap().to_string(), cafe);
+        assert_eq!(
+            hash_with(b"Caf\xe9\0x", HashOptions::pvpgn())
+                .unwrap()
+                .to_string(),
+            "ba61491e4ca50bca411d54859ae83265afd3c24a"
+        );
+    }
+
+    #[test]
+    fn test_c_compatible_lengths() {
+        let c = HashOptions::pvpgn().c_compatible(true);
+        assert_eq!(hash_with(b"\0abc", c), Err(HashError::Empty));
+
+        // strlen ends the password before the cap is reached
+        let mut long = vec![b'a'; 2000];
+        long[10] = 0;
+        assert_eq!(hash_with(&long, c), hash_with(&long[..10], c));
+        assert_eq!(
+            hash_with(&long, HashOptions::pvpgn()),
+            Err(HashError::TooLong { len: 2000 })
+        );
+
+        assert_eq!(
+            hash_with(b"12\0xyz", c.min_len(3)),
+            Err(HashError::TooShort { len: 2, min: 3 })
+        );
+    }
+
+    #[test]
+    fn test_allow_empty() {
+        let empty = "4d3aa0ee94261d5a584a6f576b8d99601546c680";
+        let options = HashOptions::pvpgn().allow_empty(true);
+        assert_eq!(hash_with(b"", options).unwrap().to_string(), empty);
+        assert_eq!(
+            hash_with(b"", options.min_len(6)).unwrap().to_string(),
+            empty
+        );
+
+        // a NUL-only password is empty once the C mode truncates it
+        let c = options.c_compatible(true);
+        assert_eq!(hash_with(b"\0", c).unwrap().to_string(), empty);
+
+        assert_eq!(
+            hash_with(b"12345", options),
+            hash_with(b"12345", HashOptions::pvpgn())
+        );
+        assert_eq!(
+            hash_with(b"", HashOptions::pvpgn().allow_empty(false)),
+            Err(HashError::Empty)
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! A token-bucket limit in front of the hash, for endpoints where repeated
+//! hashing could be used to tie up the server.
+//!
+//! A [`RateLimitedHasher`] only limits the calls made through it. Whether one
+//! instance guards a single client address, an account or the whole server
+//! is up to the caller; keep one per key in a map for per-IP limits.
+
+use crate::{calculate_hash, hash, hash_ascii, Hash, HashError};
+use std::time::{Duration, Instant};
+
+/// A monotonic time source, so tests can drive the limiter by hand.
+pub trait Clock {
+    /// Time elapsed since some fixed, arbitrary start.
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+/// Reads [`Instant`], counting from when the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Hashes at most `capacity` passwords per `interval`, refilling continuously.
+///
+/// The bucket starts full, so a burst of `capacity` calls is allowed at once.
+/// Every call takes a token before its input is checked, so rejected and
+/// malformed attempts count too. Once the bucket is empty, calls fail with
+/// [`HashError::RateLimited`] without hashing.
+#[derive(Debug, Clone)]
+pub struct RateLimitedHasher<C = SystemClock> {
+    capacity: f64,
+    interval: Duration,
+    tokens: f64,
+    last: Duration,
+    clock: C,
+}
+
+impl RateLimitedHasher {
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        RateLimitedHasher::with_clock(capacity, interval, SystemClock::new())
+    }
+}
+
+impl<C: Clock> RateLimitedHasher<C> {
+    pub fn with_clock(capacity: u32, interval: Duration, clock: C) -> Self {
+        let last = clock.now();
+        RateLimitedHasher {
+            capacity: f64::from(capacity),
+            interval,
+            tokens: f64::from(capacity),
+            last,
+            clock,
+        }
+    }
+
+    /// Hashes `password` like [`hash_bytes_iter`](crate::hash_bytes_iter) if
+    /// a token is available.
+    pub fn hash(&mut self, password: &[u8]) -> Result<Hash, HashError> {
+        self.take()?;
+        hash_ascii(password)
+    }
+
+    /// Checks `password` like [`verify`](crate::verify) if a token is
+    /// available.
+    pub fn verify(&mut self, password: &str, stored: &Hash) -> Result<bool, HashError> {
+        self.take()?;
+        let hash = calculate_hash(password)?;
+        Ok(hash::ct_eq(&hash, stored))
+    }
+
+    fn take(&mut self) -> Result<(), HashError> {
+        let now = self.clock.now();
+        let elapsed = now.saturating_sub(self.last);
+        self.last = now;
+
+        let refill = if self.interval.is_zero() {
+            self.capacity
+        } else {
+            elapsed.as_secs_f64() / self.interval.as_secs_f64() * self.capacity
+        };
+        self.tokens = (self.tokens + refill).min(self.capacity);
+
+        if self.tokens < 1.0 {
+            return Err(HashError::RateLimited);
+        }
+        self.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ratelimit::{Clock, RateLimitedHasher};
+    use crate::HashError;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct MockClock(Cell<Duration>);
+
+    impl MockClock {
+        fn advance(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_burst_then_limited() {
+        let clock = MockClock::default();
+        let mut hasher = RateLimitedHasher::with_clock(3, SECOND, &clock);
+
+        for _ in 0..3 {
+            assert!(hasher.hash(b"12345").is_ok());
+        }
+        assert_eq!(hasher.hash(b"12345"), Err(HashError::RateLimited));
+        assert_eq!(
+            hasher.verify("12345", &[0; 20].into()),
+            Err(HashError::RateLimited)
+        );
+    }
+
+    #[test]
+    fn test_refills_over_time() {
+        let clock = MockClock::default();
+        let mut hasher = RateLimitedHasher::with_clock(4, SECOND, &clock);
+        for _ in 0..4 {
+            hasher.hash(b"a").unwrap();
+        }
+
+        // a quarter of the interval buys back one token
+        clock.advance(SECOND / 4);
+        assert!(hasher.hash(b"a").is_ok());
+        assert_eq!(hasher.hash(b"a"), Err(HashError::RateLimited));
+
+        // a long idle period refills only up to capacity
+        clock.advance(SECOND * 60);
+        for _ in 0..4 {
+            assert!(hasher.hash(b"a").is_ok());
+        }
+        assert_eq!(hasher.hash(b"a"), Err(HashError::RateLimited));
+    }
+
+    #[test]
+    fn test_invalid_input_uses_a_token() {
+        let clock = MockClock::default();
+        let mut hasher = RateLimitedHasher::with_clock(1, SECOND, &clock);
+        assert_eq!(hasher.hash(b""), Err(HashError::Empty));
+        assert_eq!(hasher.hash(b"a"), Err(HashError::RateLimited));
+    }
+
+    #[test]
+    fn test_verify_when_allowed() {
+        let clock = MockClock::default();
+        let mut hasher = RateLimitedHasher::with_clock(2, SECOND, &clock);
+        let stored = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4".parse().unwrap();
+        assert_eq!(hasher.verify("12345", &stored), Ok(true));
+        assert_eq!(hasher.verify("54321", &stored), Ok(false));
+    }
+
+    #[test]
+    fn test_zero_capacity_always_limits() {
+        let mut hasher = RateLimitedHasher::new(0, SECOND);
+        assert_eq!(hasher.hash(b"a"), Err(HashError::RateLimited));
+    }
+}
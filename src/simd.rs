@@ -0,0 +1,140 @@
+//! Bulk hashing with the message expansion run for eight passwords at once.
+//!
+//! Each of the 64 expansion steps is independent across passwords, so the
+//! words of eight blocks are transposed into `u32x8` lanes and expanded
+//! together. The 80 compression rounds are inherently serial and still run
+//! one password at a time.
+
+use crate::{
+    check_len, compress_into, set_word_at, state_bytes, word_at, Hash, HashError, EXPANSION_OUT,
+    EXPANSION_ROUNDS, EXPANSION_TAPS, IV,
+};
+use wide::u32x8;
+
+const LANES: usize = 8;
+
+/// Hashes every password like [`hash_bytes_iter`](crate::hash_bytes_iter),
+/// returning one result per entry in order. A failing entry does not stop
+/// the batch.
+pub fn hash_batch_simd(passwords: &[&[u8]]) -> Vec<Result<Hash, HashError>> {
+    let mut results = Vec::with_capacity(passwords.len());
+
+    for chunk in passwords.chunks(LANES) {
+        let mut words = [[0u32; 16]; LANES];
+        let mut errors = [None; LANES];
+
+        for (lane, password) in chunk.iter().enumerate() {
+            match check_len(password.len()) {
+                Ok(()) => words[lane] = load_words(password),
+                Err(e) => errors[lane] = Some(e),
+            }
+        }
+
+        let expanded = expand_lanes(&words);
+
+        for (lane, error) in errors.into_iter().take(chunk.len()).enumerate() {
+            results.push(match error {
+                Some(e) => Err(e),
+                None => Ok(finish(&expanded, lane)),
+            });
+        }
+    }
+
+    results
+}
+
+// The expansion overwrites words 16..80 before the rounds read them, so only
+// the first 64 bytes of a password reach the digest.
+fn load_words(password: &[u8]) -> [u32; 16] {
+    let mut bytes = [0u8; 64];
+    for (dst, src) in bytes.iter_mut().zip(password) {
+        *dst = src.to_ascii_lowercase();
+    }
+
+    let mut words = [0u32; 16];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+// Vector form of `expand` over the 80 words the rounds consume.
+fn expand_lanes(words: &[[u32; 16]; LANES]) -> [u32x8; 80] {
+    let mut w = [u32x8::ZERO; 80];
+    for (i, word) in w.iter_mut().enumerate().take(16) {
+        *word = u32x8::new(std::array::from_fn(|lane| words[lane][i]));
+    }
+
+    let mask = u32x8::splat(0x1f);
+    let one = u32x8::splat(1);
+    for i in 0..EXPANSION_ROUNDS {
+        // rol(1, s) is 1 << s for every s in 0..32
+        let shift_val = EXPANSION_TAPS
+            .iter()
+            .fold(u32x8::ZERO, |acc, tap| acc ^ word_at(&w, i + tap))
+            & mask;
+        set_word_at(&mut w, i + EXPANSION_OUT, one << shift_val);
+    }
+    w
+}
+
+fn finish(expanded: &[u32x8; 80], lane: usize) -> Hash {
+    let mut block = [0u32; 256];
+    for (word, lanes) in block.iter_mut().zip(expanded) {
+        *word = lanes.as_array()[lane];
+    }
+
+    let mut state = IV;
+    compress_into(&mut state, &block);
+    Hash::from(state_bytes(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simd::{expand_lanes, hash_batch_simd, load_words, LANES};
+    use crate::{expand, hash_bytes_iter, load_block, HashError};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_batch_known_vector_and_errors() {
+        let long = [b'a'; 1025];
+        let results = hash_batch_simd(&[b"12345", b"", &long]);
+        assert_eq!(
+            results[0].unwrap().to_string(),
+            "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4"
+        );
+        assert_eq!(results[1], Err(HashError::Empty));
+        assert_eq!(results[2], Err(HashError::TooLong { len: 1025 }));
+    }
+
+    proptest! {
+        #[test]
+        fn test_expand_lanes_matches_scalar(
+            inputs in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..=80), LANES)
+        ) {
+            let words: [[u32; 16]; LANES] = std::array::from_fn(|lane| load_words(&inputs[lane]));
+            let expanded = expand_lanes(&words);
+
+            for (lane, input) in inputs.iter().enumerate() {
+                let mut expected = load_block(&input.to_ascii_lowercase());
+                expand(&mut expected);
+                for i in 0..80 {
+                    prop_assert_eq!(expanded[i].as_array()[lane], expected[i]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_batch_matches_scalar(
+            inputs in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..=100), 0..20)
+        ) {
+            let refs: Vec<&[u8]> = inputs.iter().map(Vec::as_slice).collect();
+            let results = hash_batch_simd(&refs);
+
+            prop_assert_eq!(results.len(), inputs.len());
+            for (input, result) in inputs.iter().zip(results) {
+                prop_assert_eq!(result, hash_bytes_iter(input.iter().copied()));
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Building blocks for an SRP-style password verifier over this hash.
+//!
+//! The classic SRP-6 verifier derivation is `x = H(salt || H(username ":"
+//! password))`, followed by `v = g^x mod N`. This module provides the two
+//! hash steps with the broken SHA-1; the modular exponentiation is left to
+//! the caller.
+//!
+//! Note that the SRP login used by PvPGN for Warcraft III (`bnetsrp3`) builds
+//! `x` with standard SHA-1 and an uppercased `username:password`, not with this
+//! hash. These functions are only compatible with verifiers that were derived
+//! with the broken SHA-1 on purpose; they will not reproduce stock Warcraft
+//! III verifiers. The test vectors below come from an independent port of the
+//! hash, not from a server.
+
+use crate::{check_len, digest, hash_ascii, Hash, HashError, MAX_INPUT_LEN};
+
+/// Computes `H(username ":" password)`, folding ASCII case like
+/// [`hash_bytes_iter`](crate::hash_bytes_iter).
+///
+/// Either part may be empty, but the joined input must fit the 1024 byte cap.
+pub fn srp_inner_hash(username: &[u8], password: &[u8]) -> Result<Hash, HashError> {
+    let len = username.len() + 1 + password.len();
+    check_len(len)?;
+
+    let mut joined = [0u8; MAX_INPUT_LEN];
+    joined[..username.len()].copy_from_slice(username);
+    joined[username.len()] = b':';
+    joined[username.len() + 1..len].copy_from_slice(password);
+    hash_ascii(&joined[..len])
+}
+
+/// Computes `H(salt || inner)` over the raw bytes, without case folding:
+/// the salt and the inner digest are binary and must be hashed as they are.
+pub fn srp_verifier_hash(salt: &[u8], inner: &Hash) -> Result<Hash, HashError> {
+    let len = salt.len() + inner.as_bytes().len();
+    check_len(len)?;
+
+    let mut joined = [0u8; MAX_INPUT_LEN];
+    joined[..salt.len()].copy_from_slice(salt);
+    joined[salt.len()..len].copy_from_slice(inner.as_bytes());
+    Ok(digest(&joined[..len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::srp::{srp_inner_hash, srp_verifier_hash};
+    use crate::{hash_bytes_iter, HashError};
+
+    const INNER: &str = "5dd25fb9f4513a4b2f16407b8fe48c5bcb863190";
+
+    #[test]
+    fn test_inner_hash() {
+        let inner = srp_inner_hash(b"Fatorin", b"12345").unwrap();
+        assert_eq!(inner.to_string(), INNER);
+        assert_eq!(srp_inner_hash(b"FATORIN", b"12345"), Ok(inner));
+        assert_eq!(Ok(inner), hash_bytes_iter(b"fatorin:12345".iter().copied()));
+    }
+
+    #[test]
+    fn test_inner_hash_length() {
+        assert!(srp_inner_hash(b"", b"").is_ok());
+        assert!(srp_inner_hash(&[b'u'; 500], &[b'p'; 523]).is_ok());
+        assert_eq!(
+            srp_inner_hash(&[b'u'; 500], &[b'p'; 524]),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    #[test]
+    fn test_verifier_hash() {
+        let inner = INNER.parse().unwrap();
+        let salt: Vec<u8> = (0..32).collect();
+        assert_eq!(
+            srp_verifier_hash(&salt, &inner).unwrap().to_string(),
+            "b7619ca6ffac9e6987c2f22be987f7839af71310"
+        );
+
+        // the salt is binary, so letter-valued bytes are not folded
+        assert_eq!(
+            srp_verifier_hash(b"ABCD", &inner).unwrap().to_string(),
+            "4c99dedfa53529eeb5757da8c3b00d81d596e47c"
+        );
+        assert_eq!(
+            srp_verifier_hash(b"abcd", &inner).unwrap().to_string(),
+            "a3a700d62640dd26ac98ce5f4cbb9a1c4583d32e"
+        );
+    }
+
+    #[test]
+    fn test_verifier_hash_length() {
+        let inner = INNER.parse().unwrap();
+        assert!(srp_verifier_hash(b"", &inner).is_ok());
+        assert_eq!(
+            srp_verifier_hash(&[0; 1005], &inner),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+}
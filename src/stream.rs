@@ -0,0 +1,138 @@
+use crate::{check_len, digest, Hash, HashError, MAX_INPUT_LEN};
+use std::fmt;
+
+/// Incremental form of the byte hashing entry points such as
+/// [`hash_bytes_iter`](crate::hash_bytes_iter).
+///
+/// Each chunk is ASCII-folded as it is appended, so feeding `"A"` then `"B"`
+/// buffers exactly what `"ab"` would. Only the byte-wise ASCII fold is
+/// supported: Unicode lowercasing cannot be applied per chunk because a code
+/// point may be split across two `update` calls. Use
+/// [`get_hash_string`](crate::get_hash_string) when Unicode folding is needed.
+#[derive(Clone)]
+pub struct BrokenSha1 {
+    buffer: [u8; MAX_INPUT_LEN],
+    len: usize,
+}
+
+impl BrokenSha1 {
+    pub fn new() -> Self {
+        BrokenSha1 {
+            buffer: [0; MAX_INPUT_LEN],
+            len: 0,
+        }
+    }
+
+    /// Appends `data`.
+    ///
+    /// Fails with `TooLong`, carrying the total the call would have reached,
+    /// if that total is over 1024 bytes. A failed call leaves the hasher as it
+    /// was, so a total of exactly 1024 is accepted and only the next non-empty
+    /// call is refused.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), HashError> {
+        let end = self.len.saturating_add(data.len());
+        if end > MAX_INPUT_LEN {
+            return Err(HashError::TooLong { len: end });
+        }
+
+        for (dst, src) in self.buffer[self.len..end].iter_mut().zip(data) {
+            *dst = src.to_ascii_lowercase();
+        }
+        self.len = end;
+        Ok(())
+    }
+
+    pub fn finalize(self) -> Result<Hash, HashError> {
+        check_len(self.len)?;
+        Ok(digest(&self.buffer[..self.len]))
+    }
+}
+
+// The buffer holds the folded password, so only its length is printed.
+impl fmt::Debug for BrokenSha1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrokenSha1")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for BrokenSha1 {
+    fn default() -> Self {
+        BrokenSha1::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hash_bytes_iter, BrokenSha1, Hash, HashError};
+
+    fn hash_chunks(chunks: &[&[u8]]) -> Result<Hash, HashError> {
+        let mut hasher = BrokenSha1::new();
+        for chunk in chunks {
+            hasher.update(chunk)?;
+        }
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_split_matches_whole() {
+        let split = hash_chunks(&[b"A", b"B"]).unwrap();
+        let whole = hash_chunks(&[b"AB"]).unwrap();
+        assert_eq!(split, whole);
+        assert_eq!(whole, hash_bytes_iter("ab".bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_known_vector() {
+        let hash = hash_chunks(&[b"12", b"", b"345"]).unwrap();
+        assert_eq!(hash.to_string(), "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(BrokenSha1::new().finalize(), Err(HashError::Empty));
+        assert_eq!(hash_chunks(&[b"", b""]), Err(HashError::Empty));
+    }
+
+    #[test]
+    fn test_too_long() {
+        let chunk = [b'a'; 600];
+        assert_eq!(
+            hash_chunks(&[&chunk, &chunk]),
+            Err(HashError::TooLong { len: 1200 })
+        );
+        assert_eq!(
+            BrokenSha1::new().update(&[b'a'; 1025]),
+            Err(HashError::TooLong { len: 1025 })
+        );
+    }
+
+    #[test]
+    fn test_cap_is_checked_per_update() {
+        let mut hasher = BrokenSha1::new();
+        hasher.update(&[b'a'; 1000]).unwrap();
+        hasher.update(&[b'a'; 24]).unwrap();
+        hasher.update(b"").unwrap();
+
+        assert_eq!(hasher.update(b"a"), Err(HashError::TooLong { len: 1025 }));
+        assert_eq!(
+            hasher.update(&[b'a'; 10]),
+            Err(HashError::TooLong { len: 1034 })
+        );
+
+        // the refused calls buffered nothing
+        assert_eq!(hasher.finalize(), hash_bytes_iter([b'a'; 1024].into_iter()));
+    }
+
+    #[test]
+    fn test_debug_hides_buffer() {
+        let mut hasher = BrokenSha1::new();
+        hasher.update(b"hunter2").unwrap();
+
+        let debug = format!("{:?}", hasher);
+        assert_eq!(debug, "BrokenSha1 { len: 7, .. }");
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("104"));
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use crate::{hash_ascii, Hash, HashError};
+
+/// Digests of a banned-password list, for rejecting known-weak passwords.
+///
+/// The wordlist is hashed once up front like
+/// [`hash_bytes_iter`](crate::hash_bytes_iter), so each lookup costs one hash
+/// and a set probe. Matching follows the hash, so a banned `password` also
+/// catches `PASSWORD` and anything that only differs past byte 64.
+#[derive(Debug, Clone, Default)]
+pub struct WeakPasswordSet {
+    hashes: HashSet<Hash>,
+}
+
+impl WeakPasswordSet {
+    /// Hashes every entry of `words`. The first entry that cannot be hashed,
+    /// because it is empty or over 1024 bytes, fails the whole call.
+    pub fn new(words: &[&[u8]]) -> Result<Self, HashError> {
+        let hashes = words
+            .iter()
+            .map(|word| hash_ascii(word))
+            .collect::<Result<_, _>>()?;
+        Ok(WeakPasswordSet { hashes })
+    }
+
+    /// Returns true when `password` hashes to one of the banned digests.
+    pub fn contains_password(&self, password: &[u8]) -> Result<bool, HashError> {
+        Ok(self.hashes.contains(&hash_ascii(password)?))
+    }
+
+    /// Returns true when `hash` is one of the banned digests, for checking a
+    /// stored hash without the password.
+    pub fn contains_hash(&self, hash: &Hash) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    /// Returns the number of distinct digests. Entries that hash alike are
+    /// only counted once.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true when the set has no digests.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_hash_hex, HashError, WeakPasswordSet};
+
+    const BANNED: &[&[u8]] = &[b"12345", b"password", b"admin", b"Admin", b"qwerty"];
+
+    #[test]
+    fn test_contains_password() {
+        let set = WeakPasswordSet::new(BANNED).unwrap();
+        assert_eq!(set.len(), 4);
+        assert!(!set.is_empty());
+
+        assert_eq!(set.contains_password(b"12345"), Ok(true));
+        assert_eq!(set.contains_password(b"PASSWORD"), Ok(true));
+        assert_eq!(set.contains_password(b"correct horse"), Ok(false));
+        assert_eq!(set.contains_password(b""), Err(HashError::Empty));
+
+        let stored = parse_hash_hex("460e0af6c1828a93fe887cbe103d6ca6ab97a0e4").unwrap();
+        assert!(set.contains_hash(&stored));
+    }
+
+    #[test]
+    fn test_new_errors() {
+        assert_eq!(
+            WeakPasswordSet::new(&[b"12345", b""]).unwrap_err(),
+            HashError::Empty
+        );
+        assert_eq!(
+            WeakPasswordSet::new(&[&[b'a'; 1025]]).unwrap_err(),
+            HashError::TooLong { len: 1025 }
+        );
+        assert!(WeakPasswordSet::new(&[]).unwrap().is_empty());
+    }
+}
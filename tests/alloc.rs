@@ -0,0 +1,56 @@
+//! Counts heap allocations made by the formatting paths.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let value = f();
+    (value, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_get_hash_string_allocations() {
+    let (hex, count) = allocations(|| pvpgn_hash_rs::get_hash_string("12345").unwrap());
+    assert_eq!(hex, "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+
+    // one for the lowercased password and one for the returned string; the
+    // old per-byte formatting made over twenty
+    assert!(count <= 2, "get_hash_string made {} allocations", count);
+}
+
+#[test]
+fn test_hex_formatting_does_not_allocate() {
+    let hash = pvpgn_hash_rs::hash_prelowered(b"12345").unwrap();
+
+    let (_, count) = allocations(|| hash.to_hex_bytes());
+    assert_eq!(count, 0);
+
+    let mut out = [0u8; 40];
+    let (_, count) = allocations(|| hash.to_hex_lower_into(&mut out));
+    assert_eq!(count, 0);
+
+    let (hex, count) = allocations(|| hash.to_string());
+    assert_eq!(hex, "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4");
+    assert!(count <= 1, "to_string made {} allocations", count);
+}
@@ -0,0 +1,224 @@
+//! Every public path that should reproduce the canonical "12345" digest.
+//!
+//! New entry points belong in `paths` below, so that a convenience function
+//! that reorders bytes or skips the fold shows up here by name.
+
+use pvpgn_hash_rs::{
+    authenticate, get_hash_bytes, get_hash_bytes_raw, get_hash_colon_upper, get_hash_grouped,
+    get_hash_string, hash_all_hex, hash_bufread, hash_bytes_iter, hash_checksum, hash_in_place,
+    hash_prefixes, hash_prelowered, hash_username, hash_with, hash_with_scratch, verify,
+    verify_autodetect, verify_batch, verify_hex_ci, verify_in_account, verify_str, AuthOutcome,
+    BrokenSha1, Hash, HashOptions, HashWords, PasswordHasher, PvpgnBsha1,
+};
+
+const PASSWORD: &str = "12345";
+const HEX: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn paths() -> Vec<(&'static str, String)> {
+    let p = PASSWORD.as_bytes();
+
+    let mut paths = Vec::new();
+    paths.extend([
+        ("get_hash_bytes", hex(&get_hash_bytes(p.to_vec()).unwrap())),
+        ("get_hash_bytes_raw", hex(&get_hash_bytes_raw(p).unwrap())),
+        ("get_hash_string", get_hash_string(PASSWORD).unwrap()),
+        (
+            "get_hash_grouped",
+            get_hash_grouped(PASSWORD, 8, ' ').unwrap().replace(' ', ""),
+        ),
+        (
+            "get_hash_colon_upper",
+            get_hash_colon_upper(PASSWORD)
+                .unwrap()
+                .replace(':', "")
+                .to_ascii_lowercase(),
+        ),
+        (
+            "hash_all_hex",
+            hash_all_hex(&[PASSWORD])[0].clone().unwrap(),
+        ),
+        (
+            "hash_bytes_iter",
+            hash_bytes_iter(p.iter().copied()).unwrap().to_string(),
+        ),
+        (
+            "hash_in_place",
+            hash_in_place(&mut p.to_vec()).unwrap().to_string(),
+        ),
+        (
+            "hash_bufread",
+            hash_bufread(&mut &p[..]).unwrap().to_string(),
+        ),
+        ("hash_prelowered", hash_prelowered(p).unwrap().to_string()),
+        (
+            "hash_username",
+            hash_username(PASSWORD).unwrap().to_string(),
+        ),
+        (
+            "hash_with",
+            hash_with(p, HashOptions::pvpgn()).unwrap().to_string(),
+        ),
+        (
+            "hash_with_scratch",
+            hash_with_scratch(p, &mut [0; 256]).unwrap().to_string(),
+        ),
+        ("PvpgnBsha1", PvpgnBsha1.hash(p).unwrap().to_string()),
+        ("BrokenSha1", {
+            let mut hasher = BrokenSha1::new();
+            hasher.update(b"12").unwrap();
+            hasher.update(b"345").unwrap();
+            hasher.finalize().unwrap().to_string()
+        }),
+        ("HashWords", {
+            let words = HashWords::from(hash_prelowered(p).unwrap());
+            let joined = [words.a, words.b, words.c, words.d, words.e];
+            joined.iter().map(|w| format!("{:08x}", w)).collect()
+        }),
+        ("Hash::write_hex_to", {
+            let mut out = Vec::new();
+            hash_prelowered(p).unwrap().write_hex_to(&mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        }),
+        (
+            "hash_checksum",
+            format!("{:08x}{}", hash_checksum(p).unwrap(), &HEX[8..]),
+        ),
+        ("hash_prefixes", hex(&hash_prefixes(p, &[20]).unwrap()[0])),
+    ]);
+
+    #[cfg(feature = "instrumented")]
+    paths.push((
+        "hash_timed",
+        pvpgn_hash_rs::hash_timed(p).unwrap().0.to_string(),
+    ));
+
+    #[cfg(feature = "secrecy")]
+    paths.push(("get_hash_secret", {
+        use secrecy::ExposeSecret;
+        let secret = pvpgn_hash_rs::get_hash_secret(PASSWORD).unwrap();
+        secret.expose_secret().to_string()
+    }));
+
+    #[cfg(feature = "mmap")]
+    paths.push(("hash_mmap", {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, p).unwrap();
+        pvpgn_hash_rs::hash_mmap(file.path()).unwrap().to_string()
+    }));
+
+    #[cfg(feature = "ratelimit")]
+    paths.push(("RateLimitedHasher::hash", {
+        let mut hasher =
+            pvpgn_hash_rs::ratelimit::RateLimitedHasher::new(1, std::time::Duration::from_secs(1));
+        hasher.hash(p).unwrap().to_string()
+    }));
+
+    #[cfg(feature = "base32")]
+    {
+        let encoded = pvpgn_hash_rs::base32::get_hash_base32(PASSWORD).unwrap();
+        let decoded = pvpgn_hash_rs::base32::decode(&encoded).unwrap();
+        paths.push(("base32", decoded.to_string()));
+    }
+
+    #[cfg(feature = "diagnostics")]
+    paths.push((
+        "diagnostics::hash_debug",
+        pvpgn_hash_rs::diagnostics::hash_debug(p)
+            .unwrap()
+            .0
+            .to_string(),
+    ));
+
+    #[cfg(feature = "encodings")]
+    paths.push((
+        "hash_cp1252",
+        pvpgn_hash_rs::hash_cp1252(p).unwrap().to_string(),
+    ));
+
+    #[cfg(feature = "simd")]
+    paths.push((
+        "hash_batch_simd",
+        pvpgn_hash_rs::simd::hash_batch_simd(&[p; 9])[8]
+            .unwrap()
+            .to_string(),
+    ));
+
+    #[cfg(feature = "audit")]
+    paths.push(("audit::raw_state", {
+        let raw = pvpgn_hash_rs::audit::raw_state(p).unwrap();
+        let iv = [
+            0x67452301u32,
+            0xefcdab89,
+            0x98badcfe,
+            0x10325476,
+            0xc3d2e1f0,
+        ];
+        raw.iter()
+            .zip(iv)
+            .map(|(r, iv)| format!("{:08x}", r.wrapping_add(iv)))
+            .collect()
+    }));
+
+    paths
+}
+
+#[test]
+fn test_every_path_produces_canonical_digest() {
+    let drifted: Vec<String> = paths()
+        .into_iter()
+        .filter(|(_, actual)| actual != HEX)
+        .map(|(name, actual)| format!("{}: {}", name, actual))
+        .collect();
+    assert!(
+        drifted.is_empty(),
+        "paths drifted from {}:\n{}",
+        HEX,
+        drifted.join("\n")
+    );
+}
+
+#[test]
+fn test_every_verifier_accepts_canonical_digest() {
+    let stored: Hash = HEX.parse().unwrap();
+    let upper = HEX.to_ascii_uppercase();
+
+    assert_eq!(verify(PASSWORD, &stored), Ok(true));
+    assert_eq!(verify_str(PASSWORD, HEX), Ok(true));
+    assert_eq!(verify_hex_ci(PASSWORD, &upper), Ok(true));
+    assert_eq!(authenticate(PASSWORD, HEX), Ok(AuthOutcome::Match));
+    assert_eq!(PvpgnBsha1.verify(PASSWORD.as_bytes(), &stored), Ok(true));
+    assert_eq!(verify_autodetect(PASSWORD, HEX), Ok(AuthOutcome::Match));
+    assert_eq!(
+        verify_batch(&[(PASSWORD, HEX)]),
+        [(0, Ok(AuthOutcome::Match))]
+    );
+
+    let account = format!(
+        "\"BNET\\\\acct\\\\username\"=\"admin\"\n\"BNET\\\\acct\\\\passhash1\"=\"{}\"\n",
+        HEX
+    );
+    assert_eq!(verify_in_account(PASSWORD, &account), Ok(true));
+
+    #[cfg(feature = "rayon")]
+    assert_eq!(
+        pvpgn_hash_rs::verify_batch_par(&[(PASSWORD, HEX)]),
+        [(0, Ok(AuthOutcome::Match))]
+    );
+
+    #[cfg(feature = "ratelimit")]
+    {
+        let mut hasher =
+            pvpgn_hash_rs::ratelimit::RateLimitedHasher::new(1, std::time::Duration::from_secs(1));
+        assert_eq!(hasher.verify(PASSWORD, &stored), Ok(true));
+    }
+
+    #[cfg(feature = "base32")]
+    assert_eq!(
+        pvpgn_hash_rs::base32::verify_base32(PASSWORD, "8R70NXP1GA597ZM8FJZ10FBCMTNSF874"),
+        Ok(true)
+    );
+}
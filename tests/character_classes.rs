@@ -0,0 +1,53 @@
+//! Known-answer tests across the printable ASCII range.
+//!
+//! Provenance: no captures from a running PvPGN install were available, so
+//! every expected digest here was computed with an independent Python port of
+//! the algorithm, sharing no code with this crate, and checked to agree with
+//! it. Replace or extend them with real
+//! server captures when those turn up.
+
+use pvpgn_hash_rs::{get_hash_string, hash_bytes_iter};
+
+fn hex(password: &[u8]) -> String {
+    hash_bytes_iter(password.iter().copied())
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_digits() {
+    assert_eq!(
+        hex(b"0123456789"),
+        "2f41348ad2f1a22b6203d09c92e4013fdd8aba97"
+    );
+}
+
+#[test]
+fn test_lowercase_letters() {
+    let letters = b"abcdefghijklmnopqrstuvwxyz";
+    let expected = "6dc1f87bf61ef734f573a68829079c3f6abb0309";
+    assert_eq!(hex(letters), expected);
+    assert_eq!(hex(&letters.to_ascii_uppercase()), expected);
+}
+
+#[test]
+fn test_symbols() {
+    assert_eq!(
+        hex(b"!@#$%^&*()_+-=[]{};:,./<>?~|"),
+        "053371245b0fe7e84dfa057b974daa1d713bde37"
+    );
+}
+
+#[test]
+fn test_mixed() {
+    let expected = "b577368c1e15dcba430e40f67cf7410ee36d5710";
+    assert_eq!(hex(b"Tr0ub4dor&3"), expected);
+    assert_eq!(get_hash_string("Tr0ub4dor&3").unwrap(), expected);
+}
+
+#[test]
+fn test_every_printable_byte() {
+    // 0x20 to 0x7e, 95 bytes; only the first 64 reach the rounds
+    let printable: Vec<u8> = (0x20..0x7f).collect();
+    assert_eq!(hex(&printable), "56591bcdc95dfdecc455e7d8c1842ed9d4d3ae04");
+}
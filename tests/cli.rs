@@ -0,0 +1,66 @@
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+const HEX: &str = "460e0af6c1828a93fe887cbe103d6ca6ab97a0e4";
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pvpgn-hash"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // usage errors exit before reading, which can close the pipe under us
+    match child.stdin.take().unwrap().write_all(stdin) {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => {}
+        result => result.unwrap(),
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_hash() {
+    let out = run(&["hash"], b"12345\n");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), format!("{}\n", HEX));
+}
+
+#[test]
+fn test_verify_match() {
+    for stdin in [&b"12345\n"[..], b"12345\r\n", b"12345"] {
+        let out = run(&["verify", HEX], stdin);
+        assert_eq!(out.status.code(), Some(0));
+        assert!(out.stdout.is_empty());
+    }
+
+    let upper = HEX.to_ascii_uppercase();
+    assert_eq!(run(&["verify", &upper], b"12345\n").status.code(), Some(0));
+}
+
+#[test]
+fn test_verify_mismatch() {
+    let out = run(&["verify", HEX], b"54321\n");
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+    assert!(out.stderr.is_empty());
+
+    // spaces are part of the password
+    assert_eq!(run(&["verify", HEX], b"12345 \n").status.code(), Some(1));
+}
+
+#[test]
+fn test_verify_errors() {
+    let cases: [(&[&str], &[u8]); 4] = [
+        (&["verify", HEX], b"\n"),
+        (&["verify", "460e"], b"12345\n"),
+        (&["verify"], b"12345\n"),
+        (&["frobnicate"], b""),
+    ];
+    for (args, stdin) in cases {
+        let out = run(args, stdin);
+        assert_eq!(out.status.code(), Some(2), "{:?}", args);
+        assert!(!out.stderr.is_empty(), "{:?}", args);
+    }
+}
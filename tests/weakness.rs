@@ -0,0 +1,59 @@
+//! Concrete, reproducible weaknesses of the broken SHA-1 construction.
+//!
+//! These are here so the "do not use this for new security designs" warning
+//! rests on something checkable. Each test states the structural reason it
+//! holds, and a fix to the hash that made one of them fail would also break
+//! compatibility with every stored PvPGN password.
+//!
+//! A third deviation from SHA-1 is not tested here because it needs the
+//! message schedule: the expansion computes `rol(1, x)` instead of
+//! `rol(x, 1)`, so each of words 16 to 79 is a single set bit chosen by five
+//! bits of input, and the last 64 rounds mix in very little of the password.
+
+use pvpgn_hash_rs::{get_hash_string, hash_bytes_iter, Hash};
+
+fn hash(input: &[u8]) -> Hash {
+    hash_bytes_iter(input.iter().copied()).unwrap()
+}
+
+/// Only the first 64 bytes of a password affect its hash.
+///
+/// The input is loaded as 256 words, but the expansion then writes words 16
+/// to 79 in place and the 80 rounds never read past word 79. Words 16 and up
+/// from the input are therefore overwritten or ignored, and any two passwords
+/// that share a 64 byte prefix collide regardless of what follows.
+#[test]
+fn test_bytes_past_64_are_ignored() {
+    let prefix = [b'a'; 64];
+
+    let mut x = prefix.to_vec();
+    x.extend_from_slice(b"first suffix");
+    let mut y = prefix.to_vec();
+    y.extend_from_slice(&[b'z'; 900]);
+
+    assert_eq!(hash(&x), hash(&y));
+    assert_eq!(
+        hash(&x).to_string(),
+        "f0d123af26d952d2b1f42ef8f674b028ee6d8361"
+    );
+
+    // the 64th byte itself still counts
+    let mut z = prefix.to_vec();
+    z[63] = b'b';
+    assert_ne!(hash(&z), hash(&prefix));
+}
+
+/// There is no length padding, so trailing NUL bytes are invisible.
+///
+/// SHA-1 appends a 1 bit and the message length before compressing. Here the
+/// input is copied into a zeroed buffer and compressed as is, so a password
+/// and the same password followed by NULs load the same block.
+#[test]
+fn test_trailing_nuls_collide() {
+    assert_eq!(hash(b"a"), hash(b"a\0"));
+    assert_eq!(hash(b"a"), hash(b"a\0\0\0\0\0\0\0"));
+    assert_eq!(
+        get_hash_string("pass").unwrap(),
+        get_hash_string("pass\0").unwrap()
+    );
+}